@@ -0,0 +1,31 @@
+CREATE TABLE IF NOT EXISTS news (
+    id SERIAL PRIMARY KEY,
+    title TEXT NOT NULL,
+    category TEXT NOT NULL,
+    image_url TEXT NOT NULL,
+    content TEXT NOT NULL,
+    date TEXT NOT NULL,
+    read_minutes INT NOT NULL
+);
+
+-- date (teks bebas) diganti timestamp asli
+ALTER TABLE news ADD COLUMN IF NOT EXISTS published_at TIMESTAMPTZ NOT NULL DEFAULT now();
+
+-- Cast per baris: tanggal yang formatnya cocok tapi tidak valid (mis. 2025-13-45) tetap pakai now()
+DO $$
+DECLARE
+    r RECORD;
+BEGIN
+    FOR r IN SELECT id, date FROM news WHERE date ~ '^\d{4}-\d{2}-\d{2}' LOOP
+        BEGIN
+            UPDATE news SET published_at = r.date::timestamptz WHERE id = r.id;
+        EXCEPTION WHEN invalid_datetime_format OR datetime_field_overflow THEN
+            NULL;
+        END;
+    END LOOP;
+END $$;
+
+ALTER TABLE news DROP COLUMN IF EXISTS date;
+
+CREATE INDEX IF NOT EXISTS news_published_at_idx ON news (published_at DESC);
+CREATE INDEX IF NOT EXISTS news_category_idx ON news (category);
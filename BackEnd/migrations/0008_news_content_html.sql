@@ -0,0 +1,3 @@
+-- content = sumber Markdown, content_html = hasil render yang sudah disanitasi.
+-- Baris lama diisi ulang oleh server saat startup (content_html = '').
+ALTER TABLE news ADD COLUMN IF NOT EXISTS content_html TEXT NOT NULL DEFAULT '';
@@ -0,0 +1,53 @@
+-- Slug unik untuk URL yang SEO-friendly. Baris lama diisi dari nama_tempat / title,
+-- tabrakan diberi suffix angka (nama, nama-2, ...) sama seperti slug::unique_slug.
+-- Suffix dicek terhadap semua slug yang sudah terisi, jadi dua "foo" + satu "Foo 2" tetap unik (foo, foo-2, foo-2-2).
+CREATE FUNCTION pg_temp.slugify(input TEXT) RETURNS TEXT AS $$
+    SELECT COALESCE(
+        NULLIF(trim(BOTH '-' FROM left(regexp_replace(lower(input), '[^a-z0-9]+', '-', 'g'), 80)), ''),
+        'item'
+    )
+$$ LANGUAGE SQL IMMUTABLE;
+
+-- Isi slug baris demi baris (urut id), tiap kandidat dicek ke slug yang sudah terisi di tabel itu
+CREATE FUNCTION pg_temp.fill_slugs(tbl TEXT, title_column TEXT) RETURNS void AS $$
+DECLARE
+    r RECORD;
+    base TEXT;
+    candidate TEXT;
+    n INT;
+    taken BOOLEAN;
+BEGIN
+    EXECUTE format('ALTER TABLE %I ADD COLUMN IF NOT EXISTS slug TEXT', tbl);
+
+    FOR r IN EXECUTE format('SELECT id, %I AS title FROM %I WHERE slug IS NULL ORDER BY id', title_column, tbl) LOOP
+        base := pg_temp.slugify(r.title);
+        candidate := base;
+        n := 2;
+        LOOP
+            EXECUTE format('SELECT EXISTS(SELECT 1 FROM %I WHERE slug = $1)', tbl) INTO taken USING candidate;
+            EXIT WHEN NOT taken;
+            candidate := base || '-' || n;
+            n := n + 1;
+        END LOOP;
+        EXECUTE format('UPDATE %I SET slug = $1 WHERE id = $2', tbl) USING candidate, r.id;
+    END LOOP;
+
+    EXECUTE format('ALTER TABLE %I ALTER COLUMN slug SET NOT NULL', tbl);
+    EXECUTE format('CREATE UNIQUE INDEX IF NOT EXISTS %I ON %I (slug)', tbl || '_slug_key', tbl);
+END
+$$ LANGUAGE plpgsql;
+
+SELECT pg_temp.fill_slugs('wisata_alam', 'nama_tempat');
+SELECT pg_temp.fill_slugs('wisata_pendidikan', 'nama_tempat');
+SELECT pg_temp.fill_slugs('tempat_nongkrong', 'nama_tempat');
+SELECT pg_temp.fill_slugs('kuliner', 'nama_tempat');
+SELECT pg_temp.fill_slugs('news', 'title');
+
+-- Slug lama setelah rename, supaya link lama tetap bisa di-redirect
+CREATE TABLE IF NOT EXISTS slug_redirects (
+    table_name TEXT NOT NULL,
+    old_slug TEXT NOT NULL,
+    target_id INT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (table_name, old_slug)
+);
@@ -0,0 +1,10 @@
+-- updated_at dipakai sebagai <lastmod> di sitemap.xml
+ALTER TABLE wisata_alam ADD COLUMN IF NOT EXISTS updated_at TIMESTAMPTZ NOT NULL DEFAULT now();
+ALTER TABLE wisata_pendidikan ADD COLUMN IF NOT EXISTS updated_at TIMESTAMPTZ NOT NULL DEFAULT now();
+ALTER TABLE tempat_nongkrong ADD COLUMN IF NOT EXISTS updated_at TIMESTAMPTZ NOT NULL DEFAULT now();
+ALTER TABLE kuliner ADD COLUMN IF NOT EXISTS updated_at TIMESTAMPTZ NOT NULL DEFAULT now();
+
+ALTER TABLE news ADD COLUMN IF NOT EXISTS updated_at TIMESTAMPTZ;
+UPDATE news SET updated_at = published_at WHERE updated_at IS NULL;
+ALTER TABLE news ALTER COLUMN updated_at SET NOT NULL;
+ALTER TABLE news ALTER COLUMN updated_at SET DEFAULT now();
@@ -0,0 +1,6 @@
+-- Username/email duplikat harus ditolak database (dipetakan ke 409 Conflict oleh server).
+-- Hapus/rapikan duplikat lama dulu kalau index gagal dibuat.
+CREATE UNIQUE INDEX IF NOT EXISTS users_username_key ON users (username);
+CREATE UNIQUE INDEX IF NOT EXISTS users_email_key ON users (email);
+CREATE UNIQUE INDEX IF NOT EXISTS admin_username_key ON admin (username);
+CREATE UNIQUE INDEX IF NOT EXISTS admin_email_key ON admin (email);
@@ -0,0 +1,5 @@
+-- version naik setiap update, dikirim sebagai ETag untuk cek If-Match (412 kalau basi)
+ALTER TABLE wisata_alam ADD COLUMN IF NOT EXISTS version INT NOT NULL DEFAULT 1;
+ALTER TABLE wisata_pendidikan ADD COLUMN IF NOT EXISTS version INT NOT NULL DEFAULT 1;
+ALTER TABLE tempat_nongkrong ADD COLUMN IF NOT EXISTS version INT NOT NULL DEFAULT 1;
+ALTER TABLE kuliner ADD COLUMN IF NOT EXISTS version INT NOT NULL DEFAULT 1;
@@ -0,0 +1,13 @@
+-- Soft delete: baris yang dihapus admin masuk tempat sampah dulu (deleted_at terisi),
+-- dihapus permanen oleh purge terjadwal setelah TRASH_RETENTION_DAYS hari
+ALTER TABLE wisata_alam ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+ALTER TABLE wisata_pendidikan ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+ALTER TABLE tempat_nongkrong ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+ALTER TABLE kuliner ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+ALTER TABLE news ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS wisata_alam_deleted_at_idx ON wisata_alam (deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS wisata_pendidikan_deleted_at_idx ON wisata_pendidikan (deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS tempat_nongkrong_deleted_at_idx ON tempat_nongkrong (deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS kuliner_deleted_at_idx ON kuliner (deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS news_deleted_at_idx ON news (deleted_at) WHERE deleted_at IS NOT NULL;
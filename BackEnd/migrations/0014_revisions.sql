@@ -0,0 +1,15 @@
+-- Riwayat perubahan konten (create/update/delete/restore/rollback) beserta admin yang mengubah.
+-- before/after berisi isi baris lengkap (to_jsonb) supaya bisa di-diff dan di-rollback
+CREATE TABLE IF NOT EXISTS revisions (
+    id BIGSERIAL PRIMARY KEY,
+    table_name TEXT NOT NULL,
+    row_id INT NOT NULL,
+    action TEXT NOT NULL,
+    admin_id INT,
+    admin_username TEXT,
+    before JSONB,
+    after JSONB,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS revisions_row_idx ON revisions (table_name, row_id, id DESC);
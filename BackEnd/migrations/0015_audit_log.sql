@@ -0,0 +1,31 @@
+-- Audit log keamanan (login, registrasi, ganti password, operasi massal, ...).
+-- Append-only: UPDATE / DELETE / TRUNCATE ditolak oleh trigger
+CREATE TABLE IF NOT EXISTS audit_log (
+    id BIGSERIAL PRIMARY KEY,
+    event TEXT NOT NULL,
+    success BOOLEAN NOT NULL,
+    actor_id INT,
+    username TEXT,
+    ip TEXT,
+    user_agent TEXT,
+    details JSONB,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS audit_log_event_idx ON audit_log (event, id DESC);
+CREATE INDEX IF NOT EXISTS audit_log_username_idx ON audit_log (username, id DESC);
+CREATE INDEX IF NOT EXISTS audit_log_created_at_idx ON audit_log (created_at);
+
+CREATE OR REPLACE FUNCTION audit_log_append_only() RETURNS trigger AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS audit_log_no_update ON audit_log;
+CREATE TRIGGER audit_log_no_update BEFORE UPDATE OR DELETE ON audit_log
+    FOR EACH ROW EXECUTE FUNCTION audit_log_append_only();
+
+DROP TRIGGER IF EXISTS audit_log_no_truncate ON audit_log;
+CREATE TRIGGER audit_log_no_truncate BEFORE TRUNCATE ON audit_log
+    FOR EACH STATEMENT EXECUTE FUNCTION audit_log_append_only();
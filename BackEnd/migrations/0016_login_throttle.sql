@@ -0,0 +1,10 @@
+-- Hitungan percobaan login per akun ("account:<username>" untuk password user maupun admin,
+-- "admin_2fa:<username>" untuk kode 2FA) dan per IP ("ip:<alamat>").
+-- Percobaan dihitung sebelum diverifikasi, login yang berhasil mereset akun dan mengembalikan jatah IP.
+-- Disimpan di database supaya lockout tetap berlaku setelah server restart.
+CREATE TABLE IF NOT EXISTS login_throttle (
+    key TEXT PRIMARY KEY,
+    failures INT NOT NULL DEFAULT 0,
+    last_failure_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    locked_until TIMESTAMPTZ
+);
@@ -0,0 +1,15 @@
+-- Verifikasi email: NULL = belum diverifikasi
+ALTER TABLE users ADD COLUMN IF NOT EXISTS email_verified_at TIMESTAMPTZ;
+
+-- Token reset password / verifikasi email. Token-nya sendiri JWT bertanda tangan,
+-- tabel ini yang menjamin token hanya bisa dipakai sekali.
+CREATE TABLE IF NOT EXISTS account_tokens (
+    id TEXT PRIMARY KEY,
+    user_id INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    purpose TEXT NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    used_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS account_tokens_user_idx ON account_tokens (user_id, purpose) WHERE used_at IS NULL;
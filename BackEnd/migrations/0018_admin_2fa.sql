@@ -0,0 +1,21 @@
+-- Super admin boleh reset 2FA admin lain. Admin paling lama otomatis jadi super admin
+ALTER TABLE admin ADD COLUMN IF NOT EXISTS is_super_admin BOOLEAN NOT NULL DEFAULT false;
+UPDATE admin SET is_super_admin = true
+WHERE id = (SELECT MIN(id) FROM admin) AND NOT EXISTS (SELECT 1 FROM admin WHERE is_super_admin);
+
+-- TOTP: secret (base32) diisi saat setup, 2FA aktif kalau totp_enabled_at terisi.
+-- totp_last_step mencegah kode yang sama dipakai dua kali
+ALTER TABLE admin ADD COLUMN IF NOT EXISTS totp_secret TEXT;
+ALTER TABLE admin ADD COLUMN IF NOT EXISTS totp_enabled_at TIMESTAMPTZ;
+ALTER TABLE admin ADD COLUMN IF NOT EXISTS totp_last_step BIGINT;
+
+-- Recovery code sekali pakai, disimpan sebagai hash SHA-256
+CREATE TABLE IF NOT EXISTS admin_recovery_codes (
+    id SERIAL PRIMARY KEY,
+    admin_id INT NOT NULL REFERENCES admin(id) ON DELETE CASCADE,
+    code_hash TEXT NOT NULL,
+    used_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS admin_recovery_codes_admin_idx ON admin_recovery_codes (admin_id);
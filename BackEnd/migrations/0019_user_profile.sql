@@ -0,0 +1,17 @@
+-- Profil user (opsional, diisi lewat PATCH /api/me)
+ALTER TABLE users ADD COLUMN IF NOT EXISTS display_name TEXT;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS avatar_url TEXT;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS home_city TEXT;
+
+-- chat_logs sudah dipakai chatbot.rs tapi belum pernah ada di migration
+CREATE TABLE IF NOT EXISTS chat_logs (
+    id SERIAL PRIMARY KEY,
+    user_question TEXT NOT NULL,
+    bot_answer TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+-- Chat dari user yang login dicatat dengan user_id, ikut terhapus saat akun dihapus.
+-- Chat tamu tetap user_id NULL
+ALTER TABLE chat_logs ADD COLUMN IF NOT EXISTS user_id INT REFERENCES users(id) ON DELETE CASCADE;
+CREATE INDEX IF NOT EXISTS chat_logs_user_idx ON chat_logs (user_id) WHERE user_id IS NOT NULL;
@@ -0,0 +1,30 @@
+-- Sesi login per perangkat + refresh token (di-hash, dirotasi tiap dipakai)
+CREATE TABLE IF NOT EXISTS sessions (
+    id TEXT PRIMARY KEY,
+    -- 'user' (tabel users) atau 'admin' (tabel admin)
+    role TEXT NOT NULL,
+    account_id INT NOT NULL,
+    username TEXT NOT NULL,
+    -- Login sesi ini sudah lewat 2FA (admin)
+    mfa BOOLEAN NOT NULL DEFAULT false,
+    ip TEXT,
+    user_agent TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    last_used_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    expires_at TIMESTAMPTZ NOT NULL,
+    revoked_at TIMESTAMPTZ,
+    revoked_reason TEXT
+);
+
+CREATE INDEX IF NOT EXISTS sessions_account_idx ON sessions (role, account_id) WHERE revoked_at IS NULL;
+
+CREATE TABLE IF NOT EXISTS refresh_tokens (
+    -- SHA-256 dari refresh token, token aslinya tidak pernah disimpan
+    token_hash TEXT PRIMARY KEY,
+    session_id TEXT NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    -- Sudah ditukar dengan token baru. Dipakai lagi = dicuri, seluruh sesi dicabut
+    used_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS refresh_tokens_session_idx ON refresh_tokens (session_id);
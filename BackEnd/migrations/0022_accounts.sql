@@ -0,0 +1,135 @@
+-- User dan admin digabung jadi satu tabel accounts dengan kolom role.
+-- id user lama dipertahankan (chat_logs, account_tokens, data_exports tetap cocok),
+-- admin dapat id baru dan id lamanya disimpan di legacy_admin_id. Referensi ke id admin lama (sesi, recovery code,
+-- revisi, audit log) dipindah ke id baru.
+CREATE TABLE IF NOT EXISTS accounts (
+    id SERIAL PRIMARY KEY,
+    role TEXT NOT NULL DEFAULT 'user' CHECK (role IN ('user', 'admin')),
+    username TEXT NOT NULL,
+    password TEXT NOT NULL,
+    -- Wajib untuk user. Admin dari tabel admins (0006) tidak punya email
+    email TEXT CHECK (role = 'admin' OR email IS NOT NULL),
+    email_verified_at TIMESTAMPTZ,
+    display_name TEXT,
+    avatar_url TEXT,
+    home_city TEXT,
+    -- Khusus admin: super admin + 2FA (lihat 0018)
+    is_super_admin BOOLEAN NOT NULL DEFAULT false,
+    totp_secret TEXT,
+    totp_enabled_at TIMESTAMPTZ,
+    totp_last_step BIGINT,
+    legacy_admin_id INT UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+-- Username dan email unik untuk semua role, jadi satu endpoint login cukup
+CREATE UNIQUE INDEX IF NOT EXISTS accounts_username_key ON accounts (username);
+CREATE UNIQUE INDEX IF NOT EXISTS accounts_email_key ON accounts (email);
+CREATE INDEX IF NOT EXISTS accounts_role_idx ON accounts (role);
+
+-- Pindahkan data. Tabel lama di-rename jadi legacy_*, jadi blok ini hanya jalan sekali.
+-- Username / email admin yang bentrok dengan user: username diberi awalan admin_, email dikosongkan.
+DO $$
+DECLARE
+    admins_moved BOOLEAN := false;
+BEGIN
+    IF to_regclass('users') IS NOT NULL THEN
+        INSERT INTO accounts (id, role, username, password, email, email_verified_at, display_name, avatar_url, home_city)
+        SELECT id, 'user', username, password, email, email_verified_at, display_name, avatar_url, home_city
+        FROM users;
+        PERFORM setval(pg_get_serial_sequence('accounts', 'id'), COALESCE((SELECT MAX(id) FROM accounts), 0) + 1, false);
+
+        ALTER TABLE account_tokens DROP CONSTRAINT IF EXISTS account_tokens_user_id_fkey;
+        ALTER TABLE chat_logs DROP CONSTRAINT IF EXISTS chat_logs_user_id_fkey;
+        ALTER TABLE data_exports DROP CONSTRAINT IF EXISTS data_exports_user_id_fkey;
+        ALTER TABLE users RENAME TO legacy_users;
+    END IF;
+
+    IF to_regclass('admin') IS NOT NULL THEN
+        INSERT INTO accounts (role, username, password, email, is_super_admin, totp_secret, totp_enabled_at,
+                              totp_last_step, legacy_admin_id)
+        SELECT 'admin',
+               CASE WHEN EXISTS (SELECT 1 FROM accounts a WHERE a.username = ad.username)
+                    THEN 'admin_' || ad.username ELSE ad.username END,
+               ad.password,
+               CASE WHEN EXISTS (SELECT 1 FROM accounts a WHERE a.email = ad.email)
+                    THEN NULL ELSE ad.email END,
+               ad.is_super_admin, ad.totp_secret, ad.totp_enabled_at, ad.totp_last_step, ad.id
+        FROM admin ad
+        ORDER BY ad.id;
+        ALTER TABLE admin RENAME TO legacy_admin;
+        admins_moved := true;
+    ELSIF to_regclass('admins') IS NOT NULL THEN
+        INSERT INTO accounts (role, username, password, is_super_admin, legacy_admin_id)
+        SELECT 'admin',
+               CASE WHEN EXISTS (SELECT 1 FROM accounts a WHERE a.username = ad.username)
+                    THEN 'admin_' || ad.username ELSE ad.username END,
+               ad.password,
+               ad.id = (SELECT MIN(id) FROM admins),
+               ad.id
+        FROM admins ad
+        ORDER BY ad.id;
+        ALTER TABLE admins RENAME TO legacy_admins;
+        admins_moved := true;
+    END IF;
+
+    -- Semua yang menyimpan id admin lama ikut pindah ke id baru (dari tabel admin maupun admins).
+    -- Tabel dicek dulu: database yang hanya punya admins (0006) bisa saja belum punya tabel 2FA
+    IF admins_moved THEN
+        IF to_regclass('admin_recovery_codes') IS NOT NULL THEN
+            ALTER TABLE admin_recovery_codes DROP CONSTRAINT IF EXISTS admin_recovery_codes_admin_id_fkey;
+            UPDATE admin_recovery_codes c SET admin_id = a.id FROM accounts a WHERE a.legacy_admin_id = c.admin_id;
+        END IF;
+
+        -- Sesi admin yang sedang login ikut pindah ke id baru (token lama ditolak, refresh tetap jalan)
+        IF to_regclass('sessions') IS NOT NULL THEN
+            UPDATE sessions s SET account_id = a.id FROM accounts a
+            WHERE s.role = 'admin' AND a.legacy_admin_id = s.account_id;
+        END IF;
+
+        -- Semua revisi ditulis admin
+        IF to_regclass('revisions') IS NOT NULL THEN
+            UPDATE revisions r SET admin_id = a.id FROM accounts a WHERE a.legacy_admin_id = r.admin_id;
+        END IF;
+
+        -- Sebelum accounts, pelaku event admin.* dan trash.* selalu admin, event user.* selalu user.
+        -- id admin di details (admin.register, admin.2fa_reset) ikut dipindah. Trigger append-only
+        -- dimatikan sebentar hanya untuk perpindahan ini
+        IF to_regclass('audit_log') IS NOT NULL THEN
+            ALTER TABLE audit_log DISABLE TRIGGER audit_log_no_update;
+
+            UPDATE audit_log l SET actor_id = a.id FROM accounts a
+            WHERE a.legacy_admin_id = l.actor_id AND (l.event LIKE 'admin.%' OR l.event LIKE 'trash.%');
+
+            UPDATE audit_log l SET details = jsonb_set(l.details, '{new_admin_id}', to_jsonb(a.id)) FROM accounts a
+            WHERE l.event = 'admin.register' AND a.legacy_admin_id = (l.details->>'new_admin_id')::int;
+
+            UPDATE audit_log l SET details = jsonb_set(l.details, '{target_admin_id}', to_jsonb(a.id)) FROM accounts a
+            WHERE l.event = 'admin.2fa_reset' AND a.legacy_admin_id = (l.details->>'target_admin_id')::int;
+
+            ALTER TABLE audit_log ENABLE TRIGGER audit_log_no_update;
+        END IF;
+    END IF;
+END $$;
+
+-- Foreign key yang dulu ke users / admin sekarang ke accounts
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'account_tokens_account_fkey') THEN
+        ALTER TABLE account_tokens ADD CONSTRAINT account_tokens_account_fkey
+            FOREIGN KEY (user_id) REFERENCES accounts(id) ON DELETE CASCADE;
+    END IF;
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'chat_logs_account_fkey') THEN
+        ALTER TABLE chat_logs ADD CONSTRAINT chat_logs_account_fkey
+            FOREIGN KEY (user_id) REFERENCES accounts(id) ON DELETE CASCADE;
+    END IF;
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'data_exports_account_fkey') THEN
+        ALTER TABLE data_exports ADD CONSTRAINT data_exports_account_fkey
+            FOREIGN KEY (user_id) REFERENCES accounts(id) ON DELETE CASCADE;
+    END IF;
+    IF to_regclass('admin_recovery_codes') IS NOT NULL
+       AND NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'admin_recovery_codes_account_fkey') THEN
+        ALTER TABLE admin_recovery_codes ADD CONSTRAINT admin_recovery_codes_account_fkey
+            FOREIGN KEY (admin_id) REFERENCES accounts(id) ON DELETE CASCADE;
+    END IF;
+END $$;
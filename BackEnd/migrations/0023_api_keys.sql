@@ -0,0 +1,26 @@
+-- API key untuk partner yang membaca katalog (read-only). Key asli hanya ditampilkan sekali saat dibuat,
+-- di database hanya disimpan hash SHA-256-nya
+CREATE TABLE IF NOT EXISTS api_keys (
+    id SERIAL PRIMARY KEY,
+    name TEXT NOT NULL,
+    -- Awal key (mis. "emk_1a2b3c4d") supaya admin bisa mengenali key tanpa melihat isinya
+    prefix TEXT NOT NULL,
+    key_hash TEXT NOT NULL UNIQUE,
+    -- Kategori yang boleh dibaca: wisata_alam, wisata_pendidikan, tempat_nongkrong, kuliner, news
+    scopes TEXT[] NOT NULL,
+    rate_limit_per_minute INT NOT NULL DEFAULT 60 CHECK (rate_limit_per_minute > 0),
+    created_by INT REFERENCES accounts(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    last_used_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ
+);
+
+-- Pemakaian per key, per hari, per kategori. rejected = ditolak (di luar scope / kena rate limit)
+CREATE TABLE IF NOT EXISTS api_key_usage (
+    key_id INT NOT NULL REFERENCES api_keys(id) ON DELETE CASCADE,
+    day DATE NOT NULL,
+    category TEXT NOT NULL,
+    requests BIGINT NOT NULL DEFAULT 0,
+    rejected BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (key_id, day, category)
+);
@@ -0,0 +1,21 @@
+-- Akun user dihapus (DELETE /api/me) -> data pribadinya di audit_log ikut dihapus.
+-- Satu-satunya UPDATE yang diizinkan trigger: username diganti "deleted:<id akun>", IP, user agent dan
+-- details.email dikosongkan. Event, waktu, actor_id dan isi details lain tetap, DELETE / TRUNCATE tetap ditolak.
+CREATE OR REPLACE FUNCTION audit_log_append_only() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'UPDATE'
+       AND NEW.id = OLD.id
+       AND NEW.event = OLD.event
+       AND NEW.success = OLD.success
+       AND NEW.actor_id IS NOT DISTINCT FROM OLD.actor_id
+       AND NEW.created_at = OLD.created_at
+       AND NEW.username LIKE 'deleted:%'
+       AND NEW.ip IS NULL
+       AND NEW.user_agent IS NULL
+       AND NEW.details IS NOT DISTINCT FROM OLD.details - 'email'
+    THEN
+        RETURN NEW;
+    END IF;
+    RAISE EXCEPTION 'audit_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
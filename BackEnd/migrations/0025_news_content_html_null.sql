@@ -0,0 +1,10 @@
+-- content_html NULL = belum dirender (diisi server saat startup). '' berarti Markdown-nya memang kosong setelah
+-- disanitasi, jadi tidak dirender ulang tiap startup. Hanya jalan sekali (selama kolom masih NOT NULL).
+DO $$
+BEGIN
+    IF EXISTS (SELECT 1 FROM information_schema.columns
+               WHERE table_name = 'news' AND column_name = 'content_html' AND is_nullable = 'NO') THEN
+        ALTER TABLE news ALTER COLUMN content_html DROP NOT NULL, ALTER COLUMN content_html DROP DEFAULT;
+        UPDATE news SET content_html = NULL WHERE content_html = '';
+    END IF;
+END $$;
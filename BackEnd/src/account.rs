@@ -0,0 +1,129 @@
+use axum::{
+    extract::State,
+    http::{HeaderName, HeaderValue},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::{FromRow, PgPool};
+
+use crate::app_state::AppState;
+use crate::audit::{self, AuditEvent};
+use crate::auth::{TokenResponse, ADMIN_ROLE, USER_ROLE};
+use crate::client_info::ClientInfo;
+use crate::error::{AppJson, AppResult};
+use crate::login_guard::{self, LOGIN_SCOPE};
+use crate::password;
+use crate::session;
+use crate::two_factor::{self, MfaChallenge};
+use crate::headers::EMAIL_VERIFIED_HEADER;
+
+pub const ACCOUNT_COLUMNS: &str = "id, role, username, password, email_verified_at";
+
+/// Satu akun login di tabel accounts. `role` = "user" (pengunjung) atau "admin" (pengelola konten).
+#[derive(FromRow)]
+pub struct Account {
+    pub id: i32,
+    pub role: String,
+    pub username: String,
+    pub password: String,
+    // NULL = email belum diverifikasi
+    pub email_verified_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize)]
+pub struct LoginRequest {
+    pub username: String,
+    pub password: String,
+}
+
+/// Token langsung, atau tantangan 2FA kalau akun admin sudah mengaktifkan TOTP.
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum LoginResponse {
+    Token(TokenResponse),
+    MfaRequired(MfaChallenge),
+}
+
+pub async fn find_by_username(pool: &PgPool, username: &str) -> Result<Option<Account>, sqlx::Error> {
+    sqlx::query_as::<_, Account>(&format!("SELECT {ACCOUNT_COLUMNS} FROM accounts WHERE username = $1"))
+        .bind(username)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn find_by_id(pool: &PgPool, id: i32) -> Result<Option<Account>, sqlx::Error> {
+    sqlx::query_as::<_, Account>(&format!("SELECT {ACCOUNT_COLUMNS} FROM accounts WHERE id = $1"))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+// POST /login { "username": "...", "password": "..." } -> token (role ada di respons), untuk user maupun admin.
+// /admin_login tetap ada sebagai alias untuk frontend lama.
+pub async fn login(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    AppJson(payload): AppJson<LoginRequest>,
+) -> AppResult<([(HeaderName, HeaderValue); 1], Json<LoginResponse>)> {
+    let account = find_by_username(&state.pool, &payload.username).await?;
+    // Nama event audit ikut role akun (user.login / admin.login). Username tidak dikenal dicatat sebagai user.login
+    let event_name = format!("{}.login", account.as_ref().map_or(USER_ROLE, |a| a.role.as_str()));
+
+    let ip = client.ip.as_deref();
+    if let Err(err) = login_guard::begin_attempt(&state.pool, LOGIN_SCOPE, &payload.username, ip).await {
+        let event = AuditEvent::failure(&event_name)
+            .actor(None, &payload.username)
+            .details(json!({ "reason": "locked" }));
+        audit::record(&state.pool, &client, event).await?;
+        return Err(err);
+    }
+
+    // Username tidak ada dan password salah dijawab sama persis (pesan dan waktu hash)
+    let verified = password::verify(&payload.password, account.as_ref().map(|a| a.password.as_str()));
+    let account = match account {
+        Some(account) if verified.valid => account,
+        other => {
+            let reason = if other.is_some() { "wrong_password" } else { "unknown_user" };
+            let event = AuditEvent::failure(&event_name)
+                .actor(other.map(|a| a.id), &payload.username)
+                .details(json!({ "reason": reason }));
+            audit::record(&state.pool, &client, event).await?;
+            return Err(login_guard::invalid_credentials());
+        }
+    };
+
+    login_guard::attempt_succeeded(&state.pool, LOGIN_SCOPE, &payload.username, ip).await?;
+    // Akun lama masih bcrypt -> diganti argon2 sekarang, selagi password aslinya ada
+    if verified.needs_rehash {
+        password::upgrade_hash(&state.pool, account.id, &payload.password).await;
+    }
+
+    let verified = account.email_verified_at.is_some();
+    let header = [(EMAIL_VERIFIED_HEADER, HeaderValue::from_static(if verified { "true" } else { "false" }))];
+
+    if account.role == ADMIN_ROLE {
+        // Password benar tapi 2FA aktif -> token admin baru diberikan setelah /admin_login/verify
+        let mfa_enabled = two_factor::is_enabled(&state.pool, account.id).await?;
+        let event = AuditEvent::success(&event_name)
+            .actor(Some(account.id), &account.username)
+            .details(json!({ "mfa_required": mfa_enabled }));
+        audit::record(&state.pool, &client, event).await?;
+
+        if mfa_enabled {
+            let challenge = two_factor::issue_challenge(&state.jwt, account.id, &account.username)?;
+            return Ok((header, Json(LoginResponse::MfaRequired(challenge))));
+        }
+        let token = session::start(&state, &client, ADMIN_ROLE, account.id, &account.username, false).await?;
+        return Ok((header, Json(LoginResponse::Token(token))));
+    }
+
+    let event = AuditEvent::success(&event_name)
+        .actor(Some(account.id), &account.username)
+        .details(json!({ "email_verified": verified }));
+    audit::record(&state.pool, &client, event).await?;
+
+    let token = session::start(&state, &client, USER_ROLE, account.id, &account.username, false).await?;
+    Ok((header, Json(LoginResponse::Token(token))))
+}
@@ -0,0 +1,117 @@
+use axum::http::StatusCode;
+use chrono::{Duration, Utc};
+use jsonwebtoken::Validation;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::auth::JwtKeys;
+use crate::error::{AppError, AppResult};
+
+/// Jenis token yang dikirim lewat email. Dipakai sebagai `aud` JWT dan kolom purpose,
+/// jadi token reset password tidak bisa dipakai untuk verifikasi email (dan sebaliknya).
+#[derive(Debug, Clone, Copy)]
+pub enum Purpose {
+    PasswordReset,
+    VerifyEmail,
+}
+
+impl Purpose {
+    fn as_str(self) -> &'static str {
+        match self {
+            Purpose::PasswordReset => "password_reset",
+            Purpose::VerifyEmail => "verify_email",
+        }
+    }
+
+    fn ttl(self) -> Duration {
+        match self {
+            Purpose::PasswordReset => Duration::minutes(60),
+            Purpose::VerifyEmail => Duration::hours(48),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TokenClaims {
+    sub: i32,
+    aud: String,
+    jti: String,
+    // Email saat token dibuat. Kalau email akun sudah diganti, token lama tidak berlaku
+    email: String,
+    iat: i64,
+    exp: i64,
+}
+
+/// Akun pemilik token yang berhasil dipakai.
+pub struct Redeemed {
+    pub user_id: i32,
+    pub email: String,
+}
+
+/// Buat token baru (JWT bertanda tangan + baris di account_tokens untuk sekali pakai).
+/// Token lama dengan purpose yang sama otomatis tidak berlaku lagi.
+pub async fn issue(pool: &PgPool, keys: &JwtKeys, user_id: i32, email: &str, purpose: Purpose) -> AppResult<String> {
+    let jti = Uuid::new_v4().to_string();
+    let now = Utc::now();
+    let expires_at = now + purpose.ttl();
+
+    revoke(pool, user_id, purpose).await?;
+
+    sqlx::query("INSERT INTO account_tokens (id, user_id, purpose, expires_at) VALUES ($1, $2, $3, $4)")
+        .bind(&jti)
+        .bind(user_id)
+        .bind(purpose.as_str())
+        .bind(expires_at)
+        .execute(pool)
+        .await?;
+
+    keys.sign(&TokenClaims {
+        sub: user_id,
+        aud: purpose.as_str().to_string(),
+        jti,
+        email: email.to_string(),
+        iat: now.timestamp(),
+        exp: expires_at.timestamp(),
+    })
+}
+
+/// Batalkan semua token yang belum dipakai (mis. setelah password diganti).
+pub async fn revoke(pool: &PgPool, user_id: i32, purpose: Purpose) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE account_tokens SET used_at = now() WHERE user_id = $1 AND purpose = $2 AND used_at IS NULL")
+        .bind(user_id)
+        .bind(purpose.as_str())
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Cek tanda tangan, umur dan purpose token, lalu tandai sudah dipakai.
+/// Token yang sama tidak bisa dipakai dua kali.
+pub async fn redeem<'c>(db: impl PgExecutor<'c>, keys: &JwtKeys, token: &str, purpose: Purpose) -> AppResult<Redeemed> {
+    let invalid = || AppError::Rejected(StatusCode::BAD_REQUEST, "Token tidak valid, kedaluwarsa atau sudah dipakai".to_string());
+
+    let mut validation = Validation::default();
+    validation.set_audience(&[purpose.as_str()]);
+    let claims: TokenClaims = keys.verify(token.trim(), &validation).ok_or_else(invalid)?;
+
+    let used = sqlx::query_scalar::<_, i32>(
+        "UPDATE account_tokens t SET used_at = now()
+         FROM accounts u
+         WHERE t.id = $1 AND t.user_id = $2 AND t.purpose = $3
+           AND t.used_at IS NULL AND t.expires_at > now()
+           AND u.id = t.user_id AND u.email = $4
+         RETURNING t.user_id",
+    )
+    .bind(&claims.jti)
+    .bind(claims.sub)
+    .bind(purpose.as_str())
+    .bind(&claims.email)
+    .fetch_optional(db)
+    .await?;
+
+    match used {
+        Some(user_id) => Ok(Redeemed { user_id, email: claims.email }),
+        None => Err(invalid()),
+    }
+}
@@ -1,82 +1,98 @@
 use crate::app_state::AppState;
-use crate::user::UserSql;
+use crate::audit::{self, AuditEvent};
+use crate::auth;
+use crate::client_info::ClientInfo;
+use crate::error::AppResult;
+use crate::password;
+use crate::validation::ValidJson;
 use axum::extract::State;
-use axum::http::StatusCode;
-use axum::response::IntoResponse;
+use axum::http::{HeaderMap, StatusCode};
 use axum::{Json, debug_handler};
-use bcrypt::{DEFAULT_COST, hash, verify};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
+use validator::Validate;
+
+// Kunci pg_advisory_xact_lock untuk bootstrap admin pertama
+const BOOTSTRAP_LOCK_KEY: i64 = 0x6164_6d69_6e00;
 
 #[derive(Serialize)]
 pub struct RegisterResponse {
     message: String,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Validate)]
 pub struct RegisterRequest {
+    #[validate(length(min = 4, max = 16, message = "username harus 4-16 karakter"))]
     username: String,
+    // Dicek dengan state.password_policy
     password: String,
+    #[validate(email(message = "format email tidak valid"))]
     email: String,
 }
 
-#[derive(Deserialize)]
-pub struct LoginRequest {
-    username: String,
-    password: String,
-}
-
 #[debug_handler]
 pub async fn admin_register_handler(
     State(state): State<AppState>,
-    Json(payload): Json<RegisterRequest>,
-) -> impl IntoResponse {
-    let hashed = hash(&payload.password, DEFAULT_COST).unwrap();
-
-    let query_result = sqlx::query(
-        "INSERT INTO admin (username, password, email) VALUES ($1, $2, $3)"
-    )
-        .bind(&payload.username)
-        .bind(&hashed)
-        .bind(&payload.email)
-        .execute(&state.pool)
-        .await;
+    client: ClientInfo,
+    headers: HeaderMap,
+    ValidJson(payload): ValidJson<RegisterRequest>,
+) -> AppResult<(StatusCode, Json<RegisterResponse>)> {
+    // Admin pertama boleh daftar tanpa token (bootstrap), admin berikutnya harus dibuat oleh admin yang login
+    let has_admin: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM accounts WHERE role = 'admin')")
+        .fetch_one(&state.pool)
+        .await?;
+    let creator = if has_admin {
+        Some(auth::authorize_admin(&state, &headers).await?)
+    } else {
+        None
+    };
 
-    match query_result {
-        Ok(_) => Json(RegisterResponse {
-            message: "Success create new admin".to_string(),
-        }),
+    state.password_policy.check("password", &payload.password)?;
+    let hashed = password::hash(&payload.password)?;
 
-        Err(err) => {
-            eprintln!("DB Insert Error: {:?}", err);
-            Json(RegisterResponse {
-                message: "Failed create new admin".to_string(),
-            })
-        }
-    }
-}
+    // Cek ulang + INSERT di bawah advisory lock: dua request bootstrap bersamaan tidak bisa sama-sama
+    // lolos tanpa token dan jadi super admin. Lock lepas sendiri saat transaksi selesai
+    let mut tx = state.pool.begin().await?;
+    sqlx::query("SELECT pg_advisory_xact_lock($1)")
+        .bind(BOOTSTRAP_LOCK_KEY)
+        .execute(&mut *tx)
+        .await?;
+    let has_admin: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM accounts WHERE role = 'admin')")
+        .fetch_one(&mut *tx)
+        .await?;
+    // Request bootstrap lain keburu menang -> request ini diperlakukan seperti admin berikutnya
+    let creator = match creator {
+        None if has_admin => Some(auth::authorize_admin(&state, &headers).await?),
+        creator => creator,
+    };
 
+    // Admin pertama (bootstrap) jadi super admin, boleh reset 2FA admin lain
+    let new_id: i32 = sqlx::query_scalar(
+        "INSERT INTO accounts (role, username, password, email, is_super_admin) VALUES ('admin', $1, $2, $3, $4) RETURNING id"
+    )
+    .bind(&payload.username)
+    .bind(&hashed)
+    .bind(&payload.email)
+    .bind(!has_admin)
+    .fetch_one(&mut *tx)
+    .await?;
 
-pub async fn admin_login_handler(
-    State(state): State<AppState>,
-    Json(payload): Json<LoginRequest>,
-) -> impl IntoResponse {
-    let result = sqlx::query_as::<_, UserSql>("SELECT * FROM admin WHERE username = $1")
-        .bind(&payload.username)
-        .fetch_optional(&state.pool)
-        .await;
+    // Pelaku = admin yang membuat akun, atau admin baru itu sendiri saat bootstrap
+    let event = match &creator {
+        Some(admin) => AuditEvent::success("admin.register")
+            .actor(Some(admin.id), &admin.username)
+            .details(json!({ "new_admin_id": new_id, "new_admin": payload.username })),
+        None => AuditEvent::success("admin.register")
+            .actor(Some(new_id), &payload.username)
+            .details(json!({ "bootstrap": true })),
+    };
+    audit::record(&mut *tx, &client, event).await?;
+    tx.commit().await?;
 
-    match result {
-        Ok(Some(admin)) => {
-            if verify(&payload.password, &admin.password).unwrap_or(false) {
-                (StatusCode::OK, "Logged in").into_response()
-            } else {
-                (StatusCode::UNAUTHORIZED, "failed to login").into_response()
-            }
-        }
-        Ok(None) => (StatusCode::NOT_FOUND, "User not found").into_response(),
-        Err(err) => {
-            eprintln!("DB error: {:?}", err);
-            (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error").into_response()
-        }
-    }
+    Ok((
+        StatusCode::CREATED,
+        Json(RegisterResponse {
+            message: "Admin baru berhasil dibuat".to_string(),
+        }),
+    ))
 }
@@ -0,0 +1,334 @@
+use axum::{
+    extract::{Request, State},
+    http::{Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+use crate::app_state::AppState;
+use crate::audit::{self, AuditEvent};
+use crate::auth::AdminUser;
+use crate::client_info::ClientInfo;
+use crate::content;
+use crate::error::{AppError, AppPath, AppQuery, AppResult};
+use crate::rate_limit;
+use crate::validation::{validate_not_blank, ValidJson};
+use crate::headers::API_KEY_HEADER;
+
+const KEY_PREFIX: &str = "emk_";
+// Awal key yang disimpan apa adanya untuk ditampilkan ke admin ("emk_" + 8 karakter)
+const DISPLAY_PREFIX_LEN: usize = KEY_PREFIX.len() + 8;
+const DEFAULT_RATE_LIMIT_PER_MINUTE: i32 = 60;
+const DEFAULT_USAGE_DAYS: i32 = 30;
+const MAX_USAGE_DAYS: i32 = 365;
+// Kategori di statistik untuk request ke endpoint yang bukan katalog
+const OTHER_CATEGORY: &str = "other";
+
+/// Key yang masih aktif, hasil lookup dari header X-API-Key.
+#[derive(FromRow)]
+struct ActiveKey {
+    id: i32,
+    scopes: Vec<String>,
+    rate_limit_per_minute: i32,
+}
+
+/// Key yang lolos cek scope, ditaruh di extension request. Limit per menitnya dipakai rate_limit_middleware.
+#[derive(Debug, Clone)]
+pub struct ApiKeyClient {
+    pub id: i32,
+    pub rate_limit_per_minute: i32,
+}
+
+/// Data key untuk admin. Key asli tidak pernah ditampilkan lagi setelah dibuat.
+#[derive(Debug, Serialize, FromRow)]
+pub struct ApiKeyInfo {
+    pub id: i32,
+    pub name: String,
+    pub prefix: String,
+    pub scopes: Vec<String>,
+    pub rate_limit_per_minute: i32,
+    // Username admin pembuat (NULL kalau akunnya sudah dihapus)
+    pub created_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub requests_today: i64,
+    pub requests_total: i64,
+    pub rejected_total: i64,
+}
+
+const INFO_SELECT: &str = "SELECT k.id, k.name, k.prefix, k.scopes, k.rate_limit_per_minute,
+        a.username AS created_by, k.created_at, k.last_used_at, k.revoked_at,
+        COALESCE(SUM(u.requests) FILTER (WHERE u.day = CURRENT_DATE), 0)::BIGINT AS requests_today,
+        COALESCE(SUM(u.requests), 0)::BIGINT AS requests_total,
+        COALESCE(SUM(u.rejected), 0)::BIGINT AS rejected_total
+    FROM api_keys k
+    LEFT JOIN accounts a ON a.id = k.created_by
+    LEFT JOIN api_key_usage u ON u.key_id = k.id";
+
+#[derive(Debug, Serialize)]
+pub struct CreatedApiKey {
+    #[serde(flatten)]
+    pub info: ApiKeyInfo,
+    // Hanya muncul sekali di respons ini
+    pub key: String,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct CreateApiKeyRequest {
+    #[validate(length(min = 1, max = 100, message = "name harus 1-100 karakter"), custom(function = "validate_not_blank"))]
+    pub name: String,
+    #[validate(length(min = 1, message = "scopes tidak boleh kosong"), custom(function = "validate_scopes"))]
+    pub scopes: Vec<String>,
+    #[validate(range(min = 1, max = 10_000, message = "rate_limit_per_minute harus antara 1 dan 10000"))]
+    pub rate_limit_per_minute: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UsageQuery {
+    pub days: Option<i32>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct UsageRow {
+    pub day: NaiveDate,
+    pub category: String,
+    pub requests: i64,
+    pub rejected: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApiKeyUsage {
+    pub key: ApiKeyInfo,
+    pub usage: Vec<UsageRow>,
+}
+
+/// Scope = nama kategori konten (content::TABLES).
+fn validate_scopes(scopes: &[String]) -> Result<(), ValidationError> {
+    if scopes.iter().all(|scope| content::TABLES.iter().any(|(table, _)| table == scope)) {
+        Ok(())
+    } else {
+        Err(ValidationError::new("scope")
+            .with_message("scopes harus wisata_alam, wisata_pendidikan, tempat_nongkrong, kuliner atau news".into()))
+    }
+}
+
+fn hash_key(key: &str) -> String {
+    format!("{:x}", Sha256::digest(key.trim().as_bytes()))
+}
+
+// "emk_" + 244 bit acak, di database hanya disimpan hash SHA-256-nya
+fn new_key() -> String {
+    format!("{KEY_PREFIX}{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+/// Kategori katalog dari path request, None kalau bukan endpoint katalog.
+fn category_for(path: &str) -> Option<&'static str> {
+    // Berita ada di bawah /api/news, kategori lain langsung di root (/kuliner, /wisata_alam/{id}, ...)
+    let path = path.strip_prefix("/api").filter(|p| p.starts_with("/news")).unwrap_or(path);
+    let first = match path.trim_start_matches('/').split('/').next().unwrap_or_default() {
+        "get_kuliner" => "kuliner",
+        other => other,
+    };
+    content::TABLES.iter().map(|(table, _)| *table).find(|table| *table == first)
+}
+
+async fn find_active(pool: &PgPool, key: &str) -> Result<Option<ActiveKey>, sqlx::Error> {
+    sqlx::query_as::<_, ActiveKey>(
+        "SELECT id, scopes, rate_limit_per_minute FROM api_keys WHERE key_hash = $1 AND revoked_at IS NULL",
+    )
+    .bind(hash_key(key))
+    .fetch_optional(pool)
+    .await
+}
+
+// Key hanya untuk membaca katalog dan dalam scope-nya. Hasilnya kategori yang diakses
+fn authorize(key: &ActiveKey, method: &Method, category: Option<&'static str>) -> Result<&'static str, AppError> {
+    if !matches!(*method, Method::GET | Method::HEAD) {
+        return Err(AppError::Forbidden("API key hanya boleh membaca data".to_string()));
+    }
+    let Some(category) = category else {
+        return Err(AppError::Forbidden("Endpoint ini tidak bisa diakses dengan API key".to_string()));
+    };
+    if !key.scopes.iter().any(|scope| scope == category) {
+        return Err(AppError::Forbidden(format!("API key tidak punya akses ke kategori {category}")));
+    }
+    Ok(category)
+}
+
+// Dijalankan di background supaya request tidak menunggu tulis statistik
+fn record_usage(pool: PgPool, key_id: i32, category: &'static str, allowed: bool) {
+    tokio::spawn(async move {
+        let result = sqlx::query(
+            "WITH touched AS (UPDATE api_keys SET last_used_at = now() WHERE id = $1)
+             INSERT INTO api_key_usage (key_id, day, category, requests, rejected)
+             VALUES ($1, CURRENT_DATE, $2, $3, $4)
+             ON CONFLICT (key_id, day, category) DO UPDATE
+             SET requests = api_key_usage.requests + EXCLUDED.requests,
+                 rejected = api_key_usage.rejected + EXCLUDED.rejected",
+        )
+        .bind(key_id)
+        .bind(category)
+        .bind(i64::from(allowed))
+        .bind(i64::from(!allowed))
+        .execute(&pool)
+        .await;
+        if let Err(e) = result {
+            tracing::warn!("Failed to record API key usage for key {}: {:?}", key_id, e);
+        }
+    });
+}
+
+// Middleware: request tanpa X-API-Key jalan seperti biasa (frontend, admin).
+// Request dengan X-API-Key: key tidak dikenal / dicabut -> 401, di luar scope / bukan GET -> 403.
+// Limit per key dicek di rate_limit_middleware (429 ikut dihitung sebagai rejected).
+pub async fn api_key_middleware(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let Some(raw) = request.headers().get(&API_KEY_HEADER) else {
+        return next.run(request).await;
+    };
+    // Batas per IP dulu, supaya key palsu tidak bisa dicoba (dan dicari di database) tanpa batas
+    if let Some(response) = rate_limit::check_api_key_ip(&state, &client) {
+        return response;
+    }
+    let raw = raw.to_str().unwrap_or_default().to_string();
+
+    let key = match find_active(&state.pool, &raw).await {
+        Ok(Some(key)) => key,
+        Ok(None) => return AppError::Unauthorized("API key tidak valid atau sudah dicabut".to_string()).into_response(),
+        Err(e) => return AppError::from(e).into_response(),
+    };
+
+    let category = category_for(request.uri().path());
+    let category = match authorize(&key, request.method(), category) {
+        Ok(category) => category,
+        Err(err) => {
+            record_usage(state.pool.clone(), key.id, category.unwrap_or(OTHER_CATEGORY), false);
+            return err.into_response();
+        }
+    };
+
+    request
+        .extensions_mut()
+        .insert(ApiKeyClient { id: key.id, rate_limit_per_minute: key.rate_limit_per_minute });
+    let response = next.run(request).await;
+    record_usage(state.pool.clone(), key.id, category, response.status() != StatusCode::TOO_MANY_REQUESTS);
+    response
+}
+
+async fn find_info(pool: &PgPool, id: i32) -> AppResult<ApiKeyInfo> {
+    sqlx::query_as::<_, ApiKeyInfo>(&format!("{INFO_SELECT} WHERE k.id = $1 GROUP BY k.id, a.username"))
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::not_found("API key tidak ditemukan"))
+}
+
+// GET /api/api_keys -> semua key (aktif dulu, terbaru dulu) + ringkasan pemakaian
+pub async fn list_api_keys(State(state): State<AppState>, _admin: AdminUser) -> AppResult<Json<Vec<ApiKeyInfo>>> {
+    let keys = sqlx::query_as::<_, ApiKeyInfo>(&format!(
+        "{INFO_SELECT} GROUP BY k.id, a.username ORDER BY k.revoked_at IS NOT NULL, k.created_at DESC"
+    ))
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(keys))
+}
+
+// POST /api/api_keys { "name", "scopes": ["kuliner", ...], "rate_limit_per_minute"? } -> 201, key lengkap ditampilkan sekali
+pub async fn create_api_key(
+    State(state): State<AppState>,
+    admin: AdminUser,
+    client: ClientInfo,
+    ValidJson(payload): ValidJson<CreateApiKeyRequest>,
+) -> AppResult<(StatusCode, Json<CreatedApiKey>)> {
+    let key = new_key();
+    let mut scopes = payload.scopes;
+    scopes.sort();
+    scopes.dedup();
+
+    let id: i32 = sqlx::query_scalar(
+        "INSERT INTO api_keys (name, prefix, key_hash, scopes, rate_limit_per_minute, created_by)
+         VALUES ($1, $2, $3, $4, $5, $6) RETURNING id",
+    )
+    .bind(payload.name.trim())
+    .bind(&key[..DISPLAY_PREFIX_LEN])
+    .bind(hash_key(&key))
+    .bind(&scopes)
+    .bind(payload.rate_limit_per_minute.unwrap_or(DEFAULT_RATE_LIMIT_PER_MINUTE))
+    .bind(admin.id)
+    .fetch_one(&state.pool)
+    .await?;
+    let info = find_info(&state.pool, id).await?;
+
+    let event = AuditEvent::success("admin.api_key_created")
+        .actor(Some(admin.id), &admin.username)
+        .details(json!({
+            "key_id": info.id,
+            "name": info.name,
+            "prefix": info.prefix,
+            "scopes": info.scopes,
+            "rate_limit_per_minute": info.rate_limit_per_minute,
+        }));
+    audit::record(&state.pool, &client, event).await?;
+
+    Ok((StatusCode::CREATED, Json(CreatedApiKey { info, key })))
+}
+
+// DELETE /api/api_keys/{id} -> key dicabut, request berikutnya dengan key ini langsung 401
+pub async fn revoke_api_key(
+    State(state): State<AppState>,
+    admin: AdminUser,
+    client: ClientInfo,
+    AppPath(id): AppPath<i32>,
+) -> AppResult<StatusCode> {
+    let prefix: Option<String> = sqlx::query_scalar(
+        "UPDATE api_keys SET revoked_at = now() WHERE id = $1 AND revoked_at IS NULL RETURNING prefix",
+    )
+    .bind(id)
+    .fetch_optional(&state.pool)
+    .await?;
+    let prefix = prefix.ok_or_else(|| AppError::not_found("API key tidak ditemukan atau sudah dicabut"))?;
+
+    let event = AuditEvent::success("admin.api_key_revoked")
+        .actor(Some(admin.id), &admin.username)
+        .details(json!({ "key_id": id, "prefix": prefix }));
+    audit::record(&state.pool, &client, event).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// GET /api/api_keys/{id}/usage?days=30 -> pemakaian per hari per kategori, terbaru dulu
+pub async fn get_api_key_usage(
+    State(state): State<AppState>,
+    _admin: AdminUser,
+    AppPath(id): AppPath<i32>,
+    AppQuery(query): AppQuery<UsageQuery>,
+) -> AppResult<Json<ApiKeyUsage>> {
+    let key = find_info(&state.pool, id).await?;
+    let days = query.days.unwrap_or(DEFAULT_USAGE_DAYS).clamp(1, MAX_USAGE_DAYS);
+
+    let usage = sqlx::query_as::<_, UsageRow>(
+        "SELECT day, category, requests, rejected FROM api_key_usage
+         WHERE key_id = $1 AND day > CURRENT_DATE - $2
+         ORDER BY day DESC, category",
+    )
+    .bind(id)
+    .bind(days)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(ApiKeyUsage { key, usage }))
+}
@@ -1,6 +1,32 @@
 use sqlx::postgres::PgPool;
+use std::sync::Arc;
+
+use crate::auth::JwtKeys;
+use crate::client_info::TrustedProxies;
+use crate::mailer::Mailer;
+use crate::password::PasswordPolicy;
+use crate::rate_limit::{RateLimitConfig, RateLimiter};
+use crate::sitemap::SitemapCache;
 
 #[derive(Clone)]
 pub struct AppState {
     pub pool: PgPool,
+    // URL publik frontend (Vercel) & backend, dipakai untuk link di feed & sitemap
+    pub frontend_url: String,
+    pub api_url: String,
+    pub sitemap: SitemapCache,
+    // Berapa hari data terhapus disimpan di tempat sampah sebelum di-purge
+    pub trash_retention_days: i32,
+    pub jwt: JwtKeys,
+    // ADMIN_2FA_REQUIRED: admin tanpa 2FA hanya boleh mengakses endpoint pendaftaran 2FA
+    pub admin_2fa_required: bool,
+    // Pengirim email (SMTP, file .eml, atau hanya log)
+    pub mailer: Arc<dyn Mailer>,
+    // Aturan panjang / password umum untuk daftar, reset dan ganti password
+    pub password_policy: PasswordPolicy,
+    // Token bucket di memori (per IP, per akun, per API key) + batas per kelompok route
+    pub rate_limiter: RateLimiter,
+    pub rate_limit: RateLimitConfig,
+    // Proxy yang boleh mengisi X-Forwarded-For (lihat ClientInfo)
+    pub trusted_proxies: TrustedProxies,
 }
@@ -0,0 +1,188 @@
+use axum::{
+    extract::State,
+    http::{header, HeaderMap},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{FromRow, PgExecutor, PgPool};
+
+use crate::app_state::AppState;
+use crate::auth::AdminUser;
+use crate::client_info::ClientInfo;
+use crate::error::{AppQuery, AppResult};
+use crate::headers;
+
+const DEFAULT_PER_PAGE: i64 = 50;
+const MAX_PER_PAGE: i64 = 500;
+// Batas baris untuk export CSV sekali unduh
+const MAX_EXPORT_ROWS: i64 = 50_000;
+
+/// Satu kejadian keamanan (login, registrasi, ganti password, operasi massal, ...).
+/// Tabel audit_log hanya bisa di-INSERT, UPDATE/DELETE ditolak trigger di database.
+pub struct AuditEvent<'a> {
+    event: &'a str,
+    success: bool,
+    actor_id: Option<i32>,
+    username: Option<&'a str>,
+    details: Value,
+}
+
+impl<'a> AuditEvent<'a> {
+    pub fn success(event: &'a str) -> Self {
+        Self { event, success: true, actor_id: None, username: None, details: Value::Null }
+    }
+
+    pub fn failure(event: &'a str) -> Self {
+        Self { success: false, ..Self::success(event) }
+    }
+
+    /// Akun yang melakukan aksi. Untuk login gagal cukup username yang dicoba.
+    pub fn actor(mut self, id: Option<i32>, username: &'a str) -> Self {
+        self.actor_id = id;
+        self.username = Some(username);
+        self
+    }
+
+    pub fn details(mut self, details: Value) -> Self {
+        self.details = details;
+        self
+    }
+}
+
+/// Tulis satu baris audit. Terima pool atau transaksi (`&mut *tx`) supaya bisa ikut commit bersama aksinya.
+pub async fn record<'c>(db: impl PgExecutor<'c>, client: &ClientInfo, event: AuditEvent<'_>) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO audit_log (event, success, actor_id, username, ip, user_agent, details)
+         VALUES ($1, $2, $3, $4, $5, $6, $7)",
+    )
+    .bind(event.event)
+    .bind(event.success)
+    .bind(event.actor_id)
+    .bind(event.username)
+    .bind(&client.ip)
+    .bind(&client.user_agent)
+    .bind((!event.details.is_null()).then_some(event.details))
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct AuditEntry {
+    pub id: i64,
+    pub event: String,
+    pub success: bool,
+    pub actor_id: Option<i32>,
+    pub username: Option<String>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub details: Option<Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AuditQuery {
+    pub event: Option<String>,
+    pub success: Option<bool>,
+    pub username: Option<String>,
+    pub ip: Option<String>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+const FILTER: &str = "WHERE ($1::text IS NULL OR event = $1)
+      AND ($2::bool IS NULL OR success = $2)
+      AND ($3::text IS NULL OR username = $3)
+      AND ($4::text IS NULL OR ip = $4)
+      AND ($5::date IS NULL OR created_at >= $5)
+      AND ($6::date IS NULL OR created_at < $6 + 1)";
+
+async fn fetch_entries(pool: &PgPool, query: &AuditQuery, limit: i64, offset: i64) -> Result<Vec<AuditEntry>, sqlx::Error> {
+    sqlx::query_as::<_, AuditEntry>(&format!(
+        "SELECT * FROM audit_log {FILTER} ORDER BY id DESC LIMIT $7 OFFSET $8"
+    ))
+    .bind(&query.event)
+    .bind(query.success)
+    .bind(&query.username)
+    .bind(&query.ip)
+    .bind(query.from)
+    .bind(query.to)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+}
+
+// GET /api/audit?event=admin.login&success=false&from=2026-01-01 -> terbaru dulu, metadata halaman di header
+pub async fn list_audit(
+    State(state): State<AppState>,
+    _admin: AdminUser,
+    AppQuery(query): AppQuery<AuditQuery>,
+) -> AppResult<(HeaderMap, Json<Vec<AuditEntry>>)> {
+    let per_page = query.per_page.unwrap_or(DEFAULT_PER_PAGE).clamp(1, MAX_PER_PAGE);
+    let page = query.page.unwrap_or(1).max(1);
+
+    let total: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM audit_log {FILTER}"))
+        .bind(&query.event)
+        .bind(query.success)
+        .bind(&query.username)
+        .bind(&query.ip)
+        .bind(query.from)
+        .bind(query.to)
+        .fetch_one(&state.pool)
+        .await?;
+
+    let rows = fetch_entries(&state.pool, &query, per_page, (page - 1) * per_page).await?;
+
+    Ok((headers::paging(total, page, per_page), Json(rows)))
+}
+
+// GET /api/audit/export.csv -> filter sama dengan /api/audit, tanpa paging
+pub async fn export_audit_csv(
+    State(state): State<AppState>,
+    _admin: AdminUser,
+    AppQuery(query): AppQuery<AuditQuery>,
+) -> AppResult<Response> {
+    let rows = fetch_entries(&state.pool, &query, MAX_EXPORT_ROWS, 0).await?;
+
+    let mut csv = String::from("id,created_at,event,success,actor_id,username,ip,user_agent,details\n");
+    for row in rows {
+        let fields = [
+            row.id.to_string(),
+            row.created_at.to_rfc3339(),
+            row.event,
+            row.success.to_string(),
+            row.actor_id.map(|id| id.to_string()).unwrap_or_default(),
+            row.username.unwrap_or_default(),
+            row.ip.unwrap_or_default(),
+            row.user_agent.unwrap_or_default(),
+            row.details.map(|d| d.to_string()).unwrap_or_default(),
+        ];
+        csv.push_str(&fields.iter().map(|f| csv_field(f)).collect::<Vec<_>>().join(","));
+        csv.push('\n');
+    }
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+            (header::CONTENT_DISPOSITION, "attachment; filename=\"audit-log.csv\""),
+        ],
+        csv,
+    )
+        .into_response())
+}
+
+// Quote field CSV. Nilai yang diawali = + - @ diberi ' supaya tidak dijalankan sebagai formula di Excel
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) { format!("'{value}") } else { value.to_string() };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
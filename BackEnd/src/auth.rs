@@ -0,0 +1,224 @@
+use axum::{
+    extract::{FromRequestParts, OptionalFromRequestParts},
+    http::{header, request::Parts, HeaderMap},
+};
+use chrono::Utc;
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use crate::app_state::AppState;
+use crate::error::AppError;
+use crate::session;
+
+pub const DEFAULT_TOKEN_TTL_MINUTES: i64 = 12 * 60;
+pub const ADMIN_ROLE: &str = "admin";
+pub const USER_ROLE: &str = "user";
+
+/// Kunci JWT (HS256) + umur token. Dibuat sekali di main dari JWT_SECRET.
+#[derive(Clone)]
+pub struct JwtKeys {
+    encoding: Arc<EncodingKey>,
+    decoding: Arc<DecodingKey>,
+    pub ttl_minutes: i64,
+}
+
+impl JwtKeys {
+    pub fn new(secret: &str, ttl_minutes: i64) -> Self {
+        Self {
+            encoding: Arc::new(EncodingKey::from_secret(secret.as_bytes())),
+            decoding: Arc::new(DecodingKey::from_secret(secret.as_bytes())),
+            ttl_minutes,
+        }
+    }
+
+    /// Tanda tangani claims lain (token reset password, verifikasi email, ...) dengan secret yang sama.
+    pub fn sign<T: Serialize>(&self, claims: &T) -> Result<String, AppError> {
+        encode(&Header::default(), claims, &self.encoding)
+            .map_err(|e| AppError::Internal(format!("Failed to sign JWT: {e:?}")))
+    }
+
+    pub fn verify<T: DeserializeOwned>(&self, token: &str, validation: &Validation) -> Option<T> {
+        decode::<T>(token, &self.decoding, validation).ok().map(|data| data.claims)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Claims {
+    sub: i32,
+    // Sesi (tabel sessions) pemilik token. Logout / cabut sesi langsung mematikan token ini
+    sid: String,
+    username: String,
+    role: String,
+    // true kalau login ini sudah lewat verifikasi 2FA (TOTP / recovery code)
+    #[serde(default)]
+    mfa: bool,
+    iat: i64,
+    exp: i64,
+}
+
+#[derive(Serialize)]
+pub struct TokenResponse {
+    pub token: String,
+    pub token_type: &'static str,
+    pub expires_in: i64,
+    // "user" atau "admin", supaya frontend tahu halaman mana yang dibuka setelah login
+    pub role: String,
+    // Untuk POST /refresh saat token habis. Sekali pakai, tiap refresh dapat yang baru
+    pub refresh_token: String,
+    pub refresh_expires_in: i64,
+}
+
+/// Admin yang sedang login (dari `Authorization: Bearer <token>`).
+/// Dipakai di handler yang mengubah data supaya perubahan bisa dicatat atas nama admin.
+#[derive(Debug, Clone)]
+pub struct AdminUser {
+    pub id: i32,
+    pub username: String,
+    pub mfa: bool,
+    pub session_id: String,
+}
+
+/// Token admin yang valid, tanpa cek 2FA wajib. Hanya dipakai di endpoint pendaftaran 2FA,
+/// supaya admin yang belum punya 2FA tetap bisa mengaktifkannya saat ADMIN_2FA_REQUIRED=true.
+#[derive(Debug, Clone)]
+pub struct AdminSession(pub AdminUser);
+
+/// User biasa yang sedang login (token dari `/login`). Dipakai di endpoint `/api/me`.
+#[derive(Debug, Clone)]
+pub struct AuthUser {
+    pub id: i32,
+    pub username: String,
+    pub session_id: String,
+}
+
+/// Sesi yang sedang dipakai, dari token user maupun admin. Untuk logout dan daftar sesi.
+#[derive(Debug, Clone)]
+pub struct CurrentSession {
+    pub id: String,
+    pub role: String,
+    pub account_id: i32,
+    pub username: String,
+}
+
+/// Token akses (JWT) untuk satu sesi. Sesi dan refresh token dibuat di session::start.
+pub fn access_token(
+    keys: &JwtKeys,
+    session_id: &str,
+    id: i32,
+    username: &str,
+    role: &str,
+    mfa: bool,
+) -> Result<String, AppError> {
+    let now = Utc::now().timestamp();
+    let claims = Claims {
+        sub: id,
+        sid: session_id.to_string(),
+        username: username.to_string(),
+        role: role.to_string(),
+        mfa,
+        iat: now,
+        exp: now + keys.ttl_minutes * 60,
+    };
+
+    keys.sign(&claims)
+}
+
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+}
+
+/// Role + id pemilik token akses, hanya dari tanda tangan JWT (tanpa cek sesi ke database).
+/// Untuk hal murah seperti kunci rate limit, bukan untuk otorisasi.
+pub fn token_account(keys: &JwtKeys, headers: &HeaderMap) -> Option<(String, i32)> {
+    let token = bearer_token(headers)?;
+    let claims = keys.verify::<Claims>(token, &Validation::default())?;
+    Some((claims.role, claims.sub))
+}
+
+// Verifikasi token, role (token user tidak bisa dipakai di endpoint admin, dan sebaliknya) dan sesinya.
+// role None = token user maupun admin.
+async fn session_claims(state: &AppState, token: &str, role: Option<&str>) -> Result<Claims, AppError> {
+    let expired = || AppError::Unauthorized("Token tidak valid atau sudah kedaluwarsa".to_string());
+    let claims = decode::<Claims>(token, &state.jwt.decoding, &Validation::default())
+        .ok()
+        .map(|data| data.claims)
+        .filter(|claims| role.is_none_or(|role| claims.role == role))
+        .ok_or_else(expired)?;
+
+    if !session::touch(&state.pool, &claims.sid, &claims.role, claims.sub).await? {
+        return Err(AppError::Unauthorized("Sesi sudah berakhir, silakan login ulang".to_string()));
+    }
+    Ok(claims)
+}
+
+/// Baca dan verifikasi token admin dari header request.
+pub async fn admin_from_headers(state: &AppState, headers: &HeaderMap) -> Result<AdminUser, AppError> {
+    let token = bearer_token(headers).ok_or_else(|| AppError::Unauthorized("Login admin diperlukan".to_string()))?;
+    let claims = session_claims(state, token, Some(ADMIN_ROLE)).await?;
+    Ok(AdminUser { id: claims.sub, username: claims.username, mfa: claims.mfa, session_id: claims.sid })
+}
+
+/// Token admin + aturan 2FA wajib. Kalau ADMIN_2FA_REQUIRED aktif, token tanpa 2FA ditolak (403).
+pub async fn authorize_admin(state: &AppState, headers: &HeaderMap) -> Result<AdminUser, AppError> {
+    let admin = admin_from_headers(state, headers).await?;
+    if state.admin_2fa_required && !admin.mfa {
+        return Err(AppError::Forbidden("Aktifkan 2FA terlebih dahulu (/api/admin/2fa/setup)".to_string()));
+    }
+    Ok(admin)
+}
+
+impl FromRequestParts<AppState> for AdminUser {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        authorize_admin(state, &parts.headers).await
+    }
+}
+
+impl FromRequestParts<AppState> for AdminSession {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        admin_from_headers(state, &parts.headers).await.map(AdminSession)
+    }
+}
+
+impl FromRequestParts<AppState> for AuthUser {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let token = bearer_token(&parts.headers).ok_or_else(|| AppError::Unauthorized("Login diperlukan".to_string()))?;
+        let claims = session_claims(state, token, Some(USER_ROLE)).await?;
+        Ok(AuthUser { id: claims.sub, username: claims.username, session_id: claims.sid })
+    }
+}
+
+// Option<AuthUser>: untuk endpoint yang boleh dipakai tamu. Tanpa token, token kedaluwarsa atau sesi berakhir -> None
+impl OptionalFromRequestParts<AppState> for AuthUser {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Option<Self>, Self::Rejection> {
+        let Some(token) = bearer_token(&parts.headers) else { return Ok(None) };
+        Ok(session_claims(state, token, Some(USER_ROLE))
+            .await
+            .ok()
+            .map(|claims| AuthUser { id: claims.sub, username: claims.username, session_id: claims.sid }))
+    }
+}
+
+impl FromRequestParts<AppState> for CurrentSession {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let token = bearer_token(&parts.headers).ok_or_else(|| AppError::Unauthorized("Login diperlukan".to_string()))?;
+        let claims = session_claims(state, token, None).await?;
+        Ok(CurrentSession { id: claims.sid, role: claims.role, account_id: claims.sub, username: claims.username })
+    }
+}
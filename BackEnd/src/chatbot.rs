@@ -6,6 +6,8 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use crate::app_state::AppState;
+use crate::auth::AuthUser;
+use crate::error::{AppJson, AppResult};
 
 // 1. Struct untuk menerima data dari Frontend (Laporan Chat)
 #[derive(Deserialize)]
@@ -22,33 +24,32 @@ pub struct ChatStats {
 }
 
 // --- API 1: SIMPAN LOG CHAT (Dipanggil saat user kirim pesan) ---
+// Tamu boleh, kalau ada token user chat dicatat dengan user_id (dihapus bersama akun)
 pub async fn save_chat_log(
     State(state): State<AppState>,
-    Json(payload): Json<ChatLogRequest>,
-) -> Result<StatusCode, (StatusCode, String)> {
-    let result = sqlx::query(
-        "INSERT INTO chat_logs (user_question, bot_answer) VALUES ($1, $2)"
+    user: Option<AuthUser>,
+    AppJson(payload): AppJson<ChatLogRequest>,
+) -> AppResult<StatusCode> {
+    sqlx::query(
+        "INSERT INTO chat_logs (user_question, bot_answer, user_id) VALUES ($1, $2, $3)"
     )
     .bind(payload.question)
     .bind(payload.answer)
+    .bind(user.map(|u| u.id))
     .execute(&state.pool)
-    .await;
+    .await?;
 
-    match result {
-        Ok(_) => Ok(StatusCode::CREATED),
-        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, format!("DB Error: {}", e))),
-    }
+    Ok(StatusCode::CREATED)
 }
 
 // --- API 2: AMBIL STATISTIK (Dipanggil Admin Dashboard) ---
 pub async fn get_chat_stats(
     State(state): State<AppState>,
-) -> Result<Json<ChatStats>, (StatusCode, String)> {
+) -> AppResult<Json<ChatStats>> {
     // Hitung total baris di tabel chat_logs
     let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM chat_logs")
         .fetch_one(&state.pool)
-        .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("DB Error: {}", e)))?;
+        .await?;
 
     // Return data statistik
     Ok(Json(ChatStats {
@@ -0,0 +1,96 @@
+use axum::{
+    extract::{ConnectInfo, FromRequestParts},
+    http::{header, request::Parts, HeaderMap},
+};
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
+
+use crate::app_state::AppState;
+
+// Batas panjang supaya header aneh tidak memenuhi tabel log
+const MAX_USER_AGENT_LEN: usize = 512;
+
+/// IP dan user agent client, dipakai untuk audit log, login guard dan rate limit.
+#[derive(Debug, Clone, Default)]
+pub struct ClientInfo {
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+/// Proxy yang boleh mengisi X-Forwarded-For (server.behind_proxy / server.trusted_proxies).
+/// Default: tidak ada, IP client selalu dari koneksi TCP.
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies {
+    // Semua peer dianggap proxy: hanya aman kalau server tidak bisa diakses langsung (mis. Hugging Face Spaces)
+    any: bool,
+    ips: Vec<IpAddr>,
+}
+
+impl TrustedProxies {
+    pub fn new(behind_proxy: bool, ips: &[String]) -> Result<Self, String> {
+        let ips = ips
+            .iter()
+            .map(|ip| {
+                IpAddr::from_str(ip.trim())
+                    .map_err(|_| format!("server.trusted_proxies (TRUSTED_PROXIES): '{ip}' is not an IP address"))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { any: behind_proxy, ips })
+    }
+
+    fn trusts(&self, ip: IpAddr) -> bool {
+        self.any || self.ips.contains(&ip)
+    }
+
+    /// IP client dari koneksi + X-Forwarded-For. Header hanya dibaca kalau koneksinya dari proxy tepercaya.
+    /// Entri dibaca dari kanan (yang ditambahkan proxy kita), proxy tepercaya dilewati, entri pertama
+    /// selain itu adalah client. Entri yang bukan IP valid berarti header tidak bisa dipercaya -> IP koneksi.
+    fn client_ip(&self, peer: Option<IpAddr>, headers: &HeaderMap) -> Option<IpAddr> {
+        let peer = peer?;
+        if !self.trusts(peer) {
+            return Some(peer);
+        }
+
+        let mut client = peer;
+        let entries = headers
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .collect::<Vec<_>>();
+        for entry in entries.into_iter().rev() {
+            let Ok(ip) = IpAddr::from_str(entry) else {
+                return Some(peer);
+            };
+            client = ip;
+            // Dengan behind_proxy hanya satu hop (proxy di depan kita) yang dipercaya
+            if self.any || !self.ips.contains(&ip) {
+                break;
+            }
+        }
+        Some(client)
+    }
+}
+
+impl FromRequestParts<AppState> for ClientInfo {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let peer = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+        let ip = state.trusted_proxies.client_ip(peer, &parts.headers).map(|ip| ip.to_string());
+
+        let user_agent = parts
+            .headers
+            .get(header::USER_AGENT)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.chars().take(MAX_USER_AGENT_LEN).collect());
+
+        Ok(ClientInfo { ip, user_agent })
+    }
+}
@@ -0,0 +1,351 @@
+use serde::Deserialize;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+use std::str::FromStr;
+use tracing_subscriber::EnvFilter;
+use url::Url;
+
+use crate::auth;
+use crate::client_info::TrustedProxies;
+use crate::cors::CorsConfig;
+use crate::mailer::MailConfig;
+use crate::password::PasswordPolicy;
+use crate::rate_limit::RateLimitConfig;
+use crate::sitemap;
+use crate::trash;
+
+// File config opsional. Kalau CONFIG_FILE tidak diisi, config.toml di folder kerja dipakai kalau ada
+const DEFAULT_CONFIG_FILE: &str = "config.toml";
+// Secret HS256 minimal 256 bit, sama dengan panjang output SHA-256
+const MIN_JWT_SECRET_LEN: usize = 32;
+
+/// Semua konfigurasi server. Urutan: nilai default < file TOML < env (nama env lama tetap berlaku).
+/// Dimuat dan divalidasi sekali di main, server tidak start kalau ada yang salah.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub server: ServerConfig,
+    pub database: DatabaseConfig,
+    pub auth: AuthConfig,
+    pub content: ContentConfig,
+    pub password: PasswordPolicy,
+    pub rate_limit: RateLimitConfig,
+    pub cors: CorsConfig,
+    pub mail: MailConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    pub host: IpAddr,
+    // Hugging Face Spaces (Docker) mewajibkan port 7860, jadi itu default-nya
+    pub port: u16,
+    // Filter tracing, mis. "info" atau "capstone_be=debug,tower_http=debug"
+    pub log_level: String,
+    // URL publik frontend (Vercel) & backend, dipakai untuk link di feed, sitemap dan email
+    pub frontend_url: String,
+    pub public_api_url: String,
+    // X-Forwarded-For hanya dipercaya dari proxy: behind_proxy = semua peer (server tidak bisa diakses langsung),
+    // trusted_proxies = daftar IP proxy. Tanpa keduanya IP client diambil dari koneksi TCP
+    pub behind_proxy: bool,
+    pub trusted_proxies: Vec<String>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            host: IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            port: 7860,
+            log_level: "info".to_string(),
+            frontend_url: "http://localhost:5173".to_string(),
+            public_api_url: "http://localhost:7860".to_string(),
+            behind_proxy: false,
+            trusted_proxies: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseConfig {
+    // Wajib (DATABASE_URL)
+    pub url: String,
+    pub max_connections: u32,
+    pub min_connections: u32,
+    pub acquire_timeout_secs: u64,
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self { url: String::new(), max_connections: 10, min_connections: 0, acquire_timeout_secs: 30 }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuthConfig {
+    // Wajib (JWT_SECRET), jangan pakai nilai contoh di production
+    pub jwt_secret: String,
+    pub jwt_ttl_minutes: i64,
+    // Admin tanpa 2FA hanya boleh mengakses endpoint pendaftaran 2FA
+    pub admin_2fa_required: bool,
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        Self { jwt_secret: String::new(), jwt_ttl_minutes: auth::DEFAULT_TOKEN_TTL_MINUTES, admin_2fa_required: false }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ContentConfig {
+    pub sitemap_max_urls: usize,
+    // Berapa hari data terhapus disimpan di tempat sampah sebelum di-purge
+    pub trash_retention_days: i32,
+}
+
+impl Default for ContentConfig {
+    fn default() -> Self {
+        Self { sitemap_max_urls: sitemap::DEFAULT_MAX_URLS, trash_retention_days: trash::DEFAULT_RETENTION_DAYS }
+    }
+}
+
+/// Semua kesalahan config sekaligus, supaya tidak perlu restart berkali-kali untuk menemukannya.
+#[derive(Debug)]
+pub struct ConfigError {
+    pub source: String,
+    pub errors: Vec<String>,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Invalid configuration ({}):", self.source)?;
+        for error in &self.errors {
+            writeln!(f, "  - {error}")?;
+        }
+        Ok(())
+    }
+}
+
+// Nilai env menimpa nilai dari default / file. Env kosong dianggap tidak diisi
+#[derive(Default)]
+struct EnvOverrides {
+    errors: Vec<String>,
+}
+
+impl EnvOverrides {
+    fn var(name: &str) -> Option<String> {
+        std::env::var(name).ok().map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+    }
+
+    fn parse<T>(&mut self, name: &str, target: &mut T)
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        if let Some(value) = Self::var(name) {
+            match value.parse() {
+                Ok(parsed) => *target = parsed,
+                Err(e) => self.errors.push(format!("{name}: invalid value '{value}' ({e})")),
+            }
+        }
+    }
+
+    fn string(&mut self, name: &str, target: &mut String) {
+        if let Some(value) = Self::var(name) {
+            *target = value;
+        }
+    }
+
+    fn bool(&mut self, name: &str, target: &mut bool) {
+        if let Some(value) = Self::var(name) {
+            match value.to_lowercase().as_str() {
+                "1" | "true" | "yes" | "on" => *target = true,
+                "0" | "false" | "no" | "off" => *target = false,
+                _ => self.errors.push(format!("{name}: invalid value '{value}' (expected true or false)")),
+            }
+        }
+    }
+
+    // Daftar dipisah koma
+    fn list(&mut self, name: &str, target: &mut Vec<String>) {
+        if let Some(value) = Self::var(name) {
+            *target = value.split(',').map(str::trim).filter(|v| !v.is_empty()).map(str::to_string).collect();
+        }
+    }
+}
+
+impl Config {
+    /// Muat config dari file (CONFIG_FILE atau ./config.toml kalau ada) lalu env, kemudian validasi.
+    pub fn load() -> Result<Self, ConfigError> {
+        let (mut config, source) = Self::from_file()?;
+
+        let mut env = EnvOverrides::default();
+        config.apply_env(&mut env);
+        config.normalize();
+
+        let mut errors = env.errors;
+        errors.extend(config.validate());
+        if errors.is_empty() {
+            Ok(config)
+        } else {
+            Err(ConfigError { source, errors })
+        }
+    }
+
+    fn from_file() -> Result<(Self, String), ConfigError> {
+        let explicit = EnvOverrides::var("CONFIG_FILE").map(PathBuf::from);
+        let path = match explicit {
+            Some(path) => path,
+            None if std::path::Path::new(DEFAULT_CONFIG_FILE).exists() => PathBuf::from(DEFAULT_CONFIG_FILE),
+            None => return Ok((Self::default(), "env".to_string())),
+        };
+
+        let source = format!("{} + env", path.display());
+        let fail = |error: String| ConfigError { source: source.clone(), errors: vec![error] };
+        let text = std::fs::read_to_string(&path).map_err(|e| fail(format!("cannot read {}: {e}", path.display())))?;
+        let config = toml::from_str(&text).map_err(|e| fail(format!("{}: {e}", path.display())))?;
+        Ok((config, source))
+    }
+
+    fn apply_env(&mut self, env: &mut EnvOverrides) {
+        let server = &mut self.server;
+        env.parse("SERVER_HOST", &mut server.host);
+        env.parse("PORT", &mut server.port);
+        env.string("LOG_LEVEL", &mut server.log_level);
+        env.string("FRONTEND_BASE_URL", &mut server.frontend_url);
+        env.string("PUBLIC_API_URL", &mut server.public_api_url);
+        env.bool("BEHIND_PROXY", &mut server.behind_proxy);
+        env.list("TRUSTED_PROXIES", &mut server.trusted_proxies);
+
+        let database = &mut self.database;
+        env.string("DATABASE_URL", &mut database.url);
+        env.parse("DATABASE_MAX_CONNECTIONS", &mut database.max_connections);
+        env.parse("DATABASE_MIN_CONNECTIONS", &mut database.min_connections);
+        env.parse("DATABASE_ACQUIRE_TIMEOUT_SECS", &mut database.acquire_timeout_secs);
+
+        let auth = &mut self.auth;
+        env.string("JWT_SECRET", &mut auth.jwt_secret);
+        env.parse("JWT_TTL_MINUTES", &mut auth.jwt_ttl_minutes);
+        env.bool("ADMIN_2FA_REQUIRED", &mut auth.admin_2fa_required);
+
+        env.parse("SITEMAP_MAX_URLS", &mut self.content.sitemap_max_urls);
+        env.parse("TRASH_RETENTION_DAYS", &mut self.content.trash_retention_days);
+
+        let password = &mut self.password;
+        env.parse("PASSWORD_MIN_LENGTH", &mut password.min_length);
+        env.parse("PASSWORD_MAX_LENGTH", &mut password.max_length);
+        env.bool("PASSWORD_REJECT_COMMON", &mut password.reject_common);
+
+        let rate_limit = &mut self.rate_limit;
+        env.bool("RATE_LIMIT_ENABLED", &mut rate_limit.enabled);
+        env.parse("RATE_LIMIT_AUTH", &mut rate_limit.auth);
+        env.parse("RATE_LIMIT_READ", &mut rate_limit.read);
+        env.parse("RATE_LIMIT_READ_USER", &mut rate_limit.read_user);
+        env.parse("RATE_LIMIT_WRITE", &mut rate_limit.write);
+        env.parse("RATE_LIMIT_WRITE_USER", &mut rate_limit.write_user);
+        env.parse("RATE_LIMIT_API_KEY_IP", &mut rate_limit.api_key_ip);
+
+        let cors = &mut self.cors;
+        env.list("CORS_ALLOWED_ORIGINS", &mut cors.allowed_origins);
+        env.list("CORS_ALLOWED_METHODS", &mut cors.allowed_methods);
+        env.list("CORS_ALLOWED_HEADERS", &mut cors.allowed_headers);
+        env.bool("CORS_ALLOW_CREDENTIALS", &mut cors.allow_credentials);
+        env.parse("CORS_MAX_AGE", &mut cors.max_age_secs);
+
+        let mail = &mut self.mail;
+        env.parse("MAILER", &mut mail.mailer);
+        env.string("MAIL_FROM", &mut mail.from);
+        if let Some(url) = EnvOverrides::var("SMTP_URL") {
+            mail.smtp_url = Some(url);
+        }
+        env.string("MAIL_DIR", &mut mail.dir);
+    }
+
+    fn normalize(&mut self) {
+        self.server.frontend_url = self.server.frontend_url.trim_end_matches('/').to_string();
+        self.server.public_api_url = self.server.public_api_url.trim_end_matches('/').to_string();
+        // Tanpa allow-list, hanya frontend sendiri yang boleh memanggil API dari browser
+        if self.cors.allowed_origins.is_empty() {
+            self.cors.allowed_origins = vec![self.server.frontend_url.clone()];
+        }
+    }
+
+    fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        let mut check = |ok: bool, message: &str| {
+            if !ok {
+                errors.push(message.to_string());
+            }
+        };
+
+        let server = &self.server;
+        check(server.port != 0, "server.port (PORT) must be between 1 and 65535");
+        check(is_http_url(&server.frontend_url), "server.frontend_url (FRONTEND_BASE_URL) must be an http(s) URL");
+        check(is_http_url(&server.public_api_url), "server.public_api_url (PUBLIC_API_URL) must be an http(s) URL");
+
+        let database = &self.database;
+        check(!database.url.is_empty(), "database.url (DATABASE_URL) is required");
+        check(
+            database.url.is_empty() || database.url.starts_with("postgres://") || database.url.starts_with("postgresql://"),
+            "database.url (DATABASE_URL) must start with postgres:// or postgresql://",
+        );
+        check(database.max_connections > 0, "database.max_connections (DATABASE_MAX_CONNECTIONS) must be at least 1");
+        check(
+            database.min_connections <= database.max_connections,
+            "database.min_connections must not be greater than database.max_connections",
+        );
+        check(
+            database.acquire_timeout_secs > 0,
+            "database.acquire_timeout_secs (DATABASE_ACQUIRE_TIMEOUT_SECS) must be at least 1",
+        );
+
+        check(!self.auth.jwt_secret.is_empty(), "auth.jwt_secret (JWT_SECRET) is required");
+        check(
+            self.auth.jwt_secret.is_empty() || self.auth.jwt_secret.len() >= MIN_JWT_SECRET_LEN,
+            "auth.jwt_secret (JWT_SECRET) must be at least 32 bytes",
+        );
+        check(self.auth.jwt_ttl_minutes > 0, "auth.jwt_ttl_minutes (JWT_TTL_MINUTES) must be at least 1");
+
+        check(
+            (1..=sitemap::DEFAULT_MAX_URLS).contains(&self.content.sitemap_max_urls),
+            "content.sitemap_max_urls (SITEMAP_MAX_URLS) must be between 1 and 50000",
+        );
+        check(
+            self.content.trash_retention_days > 0,
+            "content.trash_retention_days (TRASH_RETENTION_DAYS) must be at least 1",
+        );
+
+        if let Err(e) = EnvFilter::try_new(&server.log_level) {
+            errors.push(format!("server.log_level (LOG_LEVEL): {e}"));
+        }
+        if let Err(e) = self.trusted_proxies() {
+            errors.push(e);
+        }
+        if let Err(e) = self.password.validate() {
+            errors.push(e);
+        }
+        if let Err(e) = self.cors.layer() {
+            errors.push(e);
+        }
+        if let Err(e) = self.mail.validate() {
+            errors.push(e);
+        }
+        errors
+    }
+
+    pub fn trusted_proxies(&self) -> Result<TrustedProxies, String> {
+        TrustedProxies::new(self.server.behind_proxy, &self.server.trusted_proxies)
+    }
+
+    /// Alamat bind server, mis. 0.0.0.0:7860.
+    pub fn bind_address(&self) -> SocketAddr {
+        SocketAddr::new(self.server.host, self.server.port)
+    }
+}
+
+fn is_http_url(value: &str) -> bool {
+    Url::parse(value).ok().is_some_and(|url| matches!(url.scheme(), "http" | "https") && url.host_str().is_some())
+}
@@ -0,0 +1,20 @@
+use crate::error::{AppError, AppResult};
+
+/// Tabel konten yang dikelola admin: (nama tabel, kolom judul).
+/// Kolom judul dipakai untuk slug dan untuk label di daftar sampah.
+pub const TABLES: &[(&str, &str)] = &[
+    ("wisata_alam", "nama_tempat"),
+    ("wisata_pendidikan", "nama_tempat"),
+    ("tempat_nongkrong", "nama_tempat"),
+    ("kuliner", "nama_tempat"),
+    ("news", "title"),
+];
+
+/// Nama tabel dari path (`/api/trash/{table}/...`) -> nama statis yang aman di-interpolasi ke SQL.
+pub fn find_table(name: &str) -> AppResult<(&'static str, &'static str)> {
+    TABLES
+        .iter()
+        .find(|(table, _)| *table == name)
+        .copied()
+        .ok_or_else(|| AppError::not_found("Tabel tidak dikenal"))
+}
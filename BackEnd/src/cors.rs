@@ -0,0 +1,254 @@
+use axum::http::{header, request::Parts, HeaderName, HeaderValue, Method};
+use serde::Deserialize;
+use std::time::Duration;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+use crate::headers;
+
+const DEFAULT_METHODS: &str = "GET,HEAD,POST,PUT,PATCH,DELETE,OPTIONS";
+const DEFAULT_HEADERS: &str = "authorization,content-type,if-match,x-request-id,x-api-key";
+const DEFAULT_MAX_AGE_SECS: u64 = 60 * 60;
+
+/// Satu entri CORS_ALLOWED_ORIGINS.
+#[derive(Debug, Clone)]
+enum OriginRule {
+    // "*" = semua origin (hanya tanpa credentials)
+    Any,
+    Exact(String),
+    // "https://eksplore-mas-*.vercel.app": * diganti huruf / angka / "-" (tidak bisa melewati titik)
+    Pattern { prefix: String, suffix: String },
+}
+
+impl OriginRule {
+    fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim().trim_end_matches('/');
+        if value == "*" {
+            return Ok(OriginRule::Any);
+        }
+        let Some((scheme, host)) = value.split_once("://") else {
+            return Err(format!("'{value}' must start with http:// or https://"));
+        };
+        if !matches!(scheme, "http" | "https") || host.is_empty() || host.contains('/') {
+            return Err(format!("'{value}' must be scheme://host[:port] without a path"));
+        }
+
+        match value.matches('*').count() {
+            0 => Ok(OriginRule::Exact(value.to_lowercase())),
+            1 => {
+                let (prefix, suffix) = value.split_once('*').unwrap_or_default();
+                Ok(OriginRule::Pattern { prefix: prefix.to_lowercase(), suffix: suffix.to_lowercase() })
+            }
+            _ => Err(format!("'{value}' may contain only one *")),
+        }
+    }
+
+    fn matches(&self, origin: &str) -> bool {
+        match self {
+            OriginRule::Any => true,
+            OriginRule::Exact(allowed) => origin == allowed,
+            OriginRule::Pattern { prefix, suffix } => {
+                origin.len() > prefix.len() + suffix.len()
+                    && origin.starts_with(prefix.as_str())
+                    && origin.ends_with(suffix.as_str())
+                    && origin[prefix.len()..origin.len() - suffix.len()]
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '-')
+            }
+        }
+    }
+}
+
+/// Bagian `[cors]` di config. Origin kosong = hanya FRONTEND_BASE_URL (diisi saat config dimuat).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CorsConfig {
+    pub allowed_origins: Vec<String>,
+    pub allowed_methods: Vec<String>,
+    pub allowed_headers: Vec<String>,
+    // true untuk auth berbasis cookie (fetch dengan credentials: "include")
+    pub allow_credentials: bool,
+    // Berapa detik browser boleh menyimpan hasil preflight
+    pub max_age_secs: u64,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        let list = |value: &str| value.split(',').map(str::to_string).collect();
+        Self {
+            allowed_origins: Vec::new(),
+            allowed_methods: list(DEFAULT_METHODS),
+            allowed_headers: list(DEFAULT_HEADERS),
+            allow_credentials: false,
+            max_age_secs: DEFAULT_MAX_AGE_SECS,
+        }
+    }
+}
+
+impl CorsConfig {
+    fn origin_rules(&self) -> Result<Vec<OriginRule>, String> {
+        if self.allowed_origins.is_empty() {
+            return Err("cors.allowed_origins (CORS_ALLOWED_ORIGINS) must not be empty".to_string());
+        }
+        let rules = self
+            .allowed_origins
+            .iter()
+            .map(|v| OriginRule::parse(v).map_err(|e| format!("cors.allowed_origins (CORS_ALLOWED_ORIGINS): {e}")))
+            .collect::<Result<Vec<_>, _>>()?;
+        // Browser menolak "Access-Control-Allow-Origin: *" untuk request dengan cookie
+        if self.allow_credentials && rules.iter().any(|rule| matches!(rule, OriginRule::Any)) {
+            return Err("cors.allowed_origins = * cannot be combined with cors.allow_credentials = true".to_string());
+        }
+        Ok(rules)
+    }
+
+    fn methods(&self) -> Result<Vec<Method>, String> {
+        self.allowed_methods
+            .iter()
+            .map(|v| {
+                Method::from_bytes(v.trim().to_uppercase().as_bytes())
+                    .map_err(|_| format!("cors.allowed_methods (CORS_ALLOWED_METHODS): '{v}' is not an HTTP method"))
+            })
+            .collect()
+    }
+
+    fn headers(&self) -> Result<Vec<HeaderName>, String> {
+        self.allowed_headers
+            .iter()
+            .map(|v| {
+                HeaderName::from_bytes(v.trim().to_lowercase().as_bytes())
+                    .map_err(|_| format!("cors.allowed_headers (CORS_ALLOWED_HEADERS): '{v}' is not a header name"))
+            })
+            .collect()
+    }
+
+    /// Layer untuk Router. Preflight OPTIONS dijawab di sini, sebelum rate limit dan auth.
+    /// Dipanggil juga saat config divalidasi, jadi error di sini muncul saat server start.
+    pub fn layer(&self) -> Result<CorsLayer, String> {
+        let rules = self.origin_rules()?;
+        Ok(CorsLayer::new()
+            // Origin yang cocok dikirim balik apa adanya (+ Vary: Origin), origin lain tidak dapat header CORS
+            .allow_origin(AllowOrigin::predicate(move |origin: &HeaderValue, _parts: &Parts| {
+                let Ok(origin) = origin.to_str() else { return false };
+                let origin = origin.to_lowercase();
+                rules.iter().any(|rule| rule.matches(&origin))
+            }))
+            .allow_methods(self.methods()?)
+            .allow_headers(self.headers()?)
+            .allow_credentials(self.allow_credentials)
+            .max_age(Duration::from_secs(self.max_age_secs))
+            // ETag (untuk If-Match), Retry-After (login ditahan / rate limit), RateLimit-* dan metadata halaman
+            // harus di-expose supaya bisa dibaca frontend
+            .expose_headers([
+                header::ETAG,
+                header::RETRY_AFTER,
+                headers::EMAIL_VERIFIED_HEADER,
+                headers::REQUEST_ID_HEADER,
+                headers::RATELIMIT_LIMIT,
+                headers::RATELIMIT_REMAINING,
+                headers::RATELIMIT_RESET,
+                headers::RATELIMIT_POLICY,
+                headers::TOTAL_COUNT_HEADER,
+                headers::PAGE_HEADER,
+                headers::PER_PAGE_HEADER,
+            ]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(value: &str) -> OriginRule {
+        OriginRule::parse(value).unwrap()
+    }
+
+    fn config(origins: &[&str], allow_credentials: bool) -> CorsConfig {
+        CorsConfig {
+            allowed_origins: origins.iter().map(|o| o.to_string()).collect(),
+            allow_credentials,
+            ..CorsConfig::default()
+        }
+    }
+
+    fn layer_error(config: &CorsConfig) -> String {
+        match config.layer() {
+            Ok(_) => panic!("config should be rejected: {config:?}"),
+            Err(e) => e,
+        }
+    }
+
+    #[test]
+    fn exact_origin_ignores_case_and_trailing_slash() {
+        let rule = rule("https://Eksplore-Mas.vercel.app/");
+        assert!(rule.matches("https://eksplore-mas.vercel.app"));
+        assert!(!rule.matches("http://eksplore-mas.vercel.app"));
+        assert!(!rule.matches("https://eksplore-mas.vercel.app.attacker.com"));
+        assert!(!rule.matches("https://eksplore-mas.vercel.app:8443"));
+    }
+
+    #[test]
+    fn wildcard_matches_one_label_part() {
+        let rule = rule("https://eksplore-mas-*.vercel.app");
+        assert!(rule.matches("https://eksplore-mas-abc123.vercel.app"));
+        assert!(rule.matches("https://eksplore-mas-git-main-team.vercel.app"));
+        // * tidak boleh kosong dan tidak bisa melewati titik
+        assert!(!rule.matches("https://eksplore-mas-.vercel.app"));
+        assert!(!rule.matches("https://eksplore-mas-a.evil.vercel.app"));
+        assert!(!rule.matches("https://eksplore-mas-a.vercel.app.attacker.com"));
+        assert!(!rule.matches("http://eksplore-mas-a.vercel.app"));
+    }
+
+    #[test]
+    fn wildcard_subdomain_edge_cases() {
+        let rule = rule("https://*.example.com");
+        assert!(rule.matches("https://app.example.com"));
+        assert!(!rule.matches("https://example.com"));
+        assert!(!rule.matches("https://evil.example.com.attacker"));
+        assert!(!rule.matches("https://evilexample.com"));
+        assert!(!rule.matches("https://a.b.example.com"));
+        assert!(!rule.matches("https://a_b.example.com"));
+    }
+
+    #[test]
+    fn any_matches_everything() {
+        assert!(rule("*").matches("https://whatever.example"));
+    }
+
+    #[test]
+    fn rejects_invalid_origins() {
+        for value in [
+            "example.com",
+            "ftp://example.com",
+            "https://",
+            "https://example.com/path",
+            "https://*.*.example.com",
+            "https://a*b*.example.com",
+        ] {
+            assert!(OriginRule::parse(value).is_err(), "'{value}' should be rejected");
+        }
+    }
+
+    #[test]
+    fn rejects_any_origin_with_credentials() {
+        let error = layer_error(&config(&["*"], true));
+        assert!(error.contains("cannot be combined"), "{error}");
+        let error = layer_error(&config(&["https://app.example.com", "*"], true));
+        assert!(error.contains("cannot be combined"), "{error}");
+
+        assert!(config(&["*"], false).layer().is_ok());
+        assert!(config(&["https://app.example.com"], true).layer().is_ok());
+    }
+
+    #[test]
+    fn rejects_empty_origins_and_bad_methods_or_headers() {
+        assert!(layer_error(&config(&[], false)).contains("must not be empty"));
+
+        let mut bad_method = config(&["https://app.example.com"], false);
+        bad_method.allowed_methods = vec!["GET".to_string(), "NOT A METHOD".to_string()];
+        assert!(layer_error(&bad_method).contains("CORS_ALLOWED_METHODS"));
+
+        let mut bad_header = config(&["https://app.example.com"], false);
+        bad_header.allowed_headers = vec!["x-ok".to_string(), "bad header".to_string()];
+        assert!(layer_error(&bad_header).contains("CORS_ALLOWED_HEADERS"));
+    }
+}
@@ -0,0 +1,228 @@
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{json, Value};
+use sqlx::{FromRow, PgPool};
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::app_state::AppState;
+use crate::audit::{self, AuditEvent};
+use crate::auth::AuthUser;
+use crate::client_info::ClientInfo;
+use crate::error::{AppError, AppPath, AppResult};
+use crate::me;
+
+// File siap diunduh selama 7 hari, setelah diunduh langsung dihapus
+const EXPORT_TTL_DAYS: i32 = 7;
+// Export yang masih "pending" selama ini dianggap gagal (mis. server restart saat proses)
+const STALE_PENDING_MINUTES: i32 = 30;
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const FORMAT_VERSION: i32 = 1;
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct ExportStatus {
+    pub id: String,
+    // pending -> ready -> downloaded, atau failed
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub ready_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub downloaded_at: Option<DateTime<Utc>>,
+}
+
+const STATUS_COLUMNS: &str = "id, status, created_at, ready_at, expires_at, downloaded_at";
+
+async fn find_export(pool: &PgPool, user_id: i32, id: &str) -> AppResult<ExportStatus> {
+    sqlx::query_as::<_, ExportStatus>(&format!(
+        "SELECT {STATUS_COLUMNS} FROM data_exports WHERE id = $1 AND user_id = $2"
+    ))
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::not_found("Export tidak ditemukan atau sudah kedaluwarsa"))
+}
+
+// Semua data pribadi user dalam satu dokumen JSON. Bagian baru (ulasan, favorit, itinerary, ...)
+// ditambahkan di sini saat fiturnya ada.
+async fn build(pool: &PgPool, user_id: i32) -> Result<Value, sqlx::Error> {
+    let profile = me::load_profile(pool, user_id).await?;
+
+    let chat_logs: Value = sqlx::query_scalar(
+        "SELECT COALESCE(jsonb_agg(to_jsonb(c) - 'user_id' ORDER BY c.id), '[]'::jsonb)
+         FROM chat_logs c WHERE c.user_id = $1",
+    )
+    .bind(user_id)
+    .fetch_one(pool)
+    .await?;
+
+    // Riwayat login / aktivitas akun dari audit log (hanya event milik user sendiri)
+    let security_events: Value = sqlx::query_scalar(
+        "SELECT COALESCE(jsonb_agg(jsonb_build_object(
+                    'event', event, 'success', success, 'ip', ip,
+                    'user_agent', user_agent, 'created_at', created_at) ORDER BY id), '[]'::jsonb)
+         FROM audit_log WHERE actor_id = $1 AND event LIKE 'user.%'",
+    )
+    .bind(user_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(json!({
+        "format_version": FORMAT_VERSION,
+        "generated_at": Utc::now(),
+        "profile": profile,
+        "chat_logs": chat_logs,
+        "security_events": security_events,
+    }))
+}
+
+async fn generate(pool: PgPool, id: String, user_id: i32) {
+    let result = match build(&pool, user_id).await {
+        Ok(data) => sqlx::query(
+            "UPDATE data_exports SET status = 'ready', data = $2, ready_at = now(),
+                    expires_at = now() + make_interval(days => $3)
+             WHERE id = $1 AND status = 'pending'",
+        )
+        .bind(&id)
+        .bind(data)
+        .bind(EXPORT_TTL_DAYS)
+        .execute(&pool)
+        .await,
+        Err(e) => {
+            tracing::error!("Failed to build data export {}: {:?}", id, e);
+            sqlx::query("UPDATE data_exports SET status = 'failed' WHERE id = $1")
+                .bind(&id)
+                .execute(&pool)
+                .await
+        }
+    };
+    if let Err(e) = result {
+        tracing::error!("Failed to save data export {}: {:?}", id, e);
+    }
+}
+
+// POST /api/me/export -> 202, file dibuat di background. Kalau masih ada export yang belum diunduh, itu yang dikembalikan
+pub async fn request_export(
+    State(state): State<AppState>,
+    user: AuthUser,
+    client: ClientInfo,
+) -> AppResult<(StatusCode, Json<ExportStatus>)> {
+    let existing = sqlx::query_as::<_, ExportStatus>(&format!(
+        "SELECT {STATUS_COLUMNS} FROM data_exports
+         WHERE user_id = $1 AND status IN ('pending', 'ready') ORDER BY created_at DESC LIMIT 1"
+    ))
+    .bind(user.id)
+    .fetch_optional(&state.pool)
+    .await?;
+    if let Some(export) = existing {
+        return Ok((StatusCode::ACCEPTED, Json(export)));
+    }
+
+    let export = sqlx::query_as::<_, ExportStatus>(&format!(
+        "INSERT INTO data_exports (id, user_id, status) VALUES ($1, $2, 'pending') RETURNING {STATUS_COLUMNS}"
+    ))
+    .bind(Uuid::new_v4().to_string())
+    .bind(user.id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    let event = AuditEvent::success("user.data_export_requested")
+        .actor(Some(user.id), &user.username)
+        .details(json!({ "export_id": export.id }));
+    audit::record(&state.pool, &client, event).await?;
+
+    tokio::spawn(generate(state.pool.clone(), export.id.clone(), user.id));
+
+    Ok((StatusCode::ACCEPTED, Json(export)))
+}
+
+// GET /api/me/export/{id} -> status export
+pub async fn get_export_status(
+    State(state): State<AppState>,
+    user: AuthUser,
+    AppPath(id): AppPath<String>,
+) -> AppResult<Json<ExportStatus>> {
+    Ok(Json(find_export(&state.pool, user.id, &id).await?))
+}
+
+// GET /api/me/export/{id}/download -> file JSON. Hanya bisa sekali: isi file dihapus setelah diunduh
+pub async fn download_export(
+    State(state): State<AppState>,
+    user: AuthUser,
+    client: ClientInfo,
+    AppPath(id): AppPath<String>,
+) -> AppResult<Response> {
+    let export = find_export(&state.pool, user.id, &id).await?;
+    match export.status.as_str() {
+        "pending" => return Err(AppError::Conflict("Export masih diproses, coba lagi nanti".to_string())),
+        "failed" => return Err(AppError::Rejected(StatusCode::GONE, "Export gagal dibuat, silakan minta ulang".to_string())),
+        "downloaded" => return Err(AppError::Rejected(StatusCode::GONE, "Export sudah diunduh dan dihapus".to_string())),
+        _ => {}
+    }
+
+    // Ambil isi sekaligus hapus, jadi dua request bersamaan tidak bisa sama-sama mengunduh
+    let data: Option<Value> = sqlx::query_scalar(
+        "UPDATE data_exports SET status = 'downloaded', downloaded_at = now(), data = NULL
+         FROM (SELECT data FROM data_exports WHERE id = $1 FOR UPDATE) old
+         WHERE data_exports.id = $1 AND status = 'ready' AND expires_at > now()
+         RETURNING old.data",
+    )
+    .bind(&id)
+    .fetch_optional(&state.pool)
+    .await?
+    .flatten();
+    let data = data.ok_or_else(|| AppError::Rejected(StatusCode::GONE, "Export sudah kedaluwarsa".to_string()))?;
+
+    let event = AuditEvent::success("user.data_export_downloaded")
+        .actor(Some(user.id), &user.username)
+        .details(json!({ "export_id": id }));
+    audit::record(&state.pool, &client, event).await?;
+
+    let body = serde_json::to_vec_pretty(&data).map_err(|e| AppError::Internal(format!("Failed to encode export: {e}")))?;
+    let filename = format!("attachment; filename=\"exploremas-data-{}.json\"", Utc::now().format("%Y%m%d"));
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/json".to_string()),
+            (header::CONTENT_DISPOSITION, filename),
+            (header::CACHE_CONTROL, "no-store".to_string()),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+/// Tiap jam: hapus export yang kedaluwarsa / sudah diunduh lama, tandai export yang macet sebagai gagal.
+pub fn spawn_cleanup_task(pool: PgPool) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(CLEANUP_INTERVAL);
+        loop {
+            interval.tick().await;
+            let result = sqlx::query(
+                "UPDATE data_exports SET status = 'failed'
+                 WHERE status = 'pending' AND created_at < now() - make_interval(mins => $1)",
+            )
+            .bind(STALE_PENDING_MINUTES)
+            .execute(&pool)
+            .await
+            .and(
+                sqlx::query(
+                    "DELETE FROM data_exports
+                     WHERE expires_at < now() OR created_at < now() - make_interval(days => $1)",
+                )
+                .bind(EXPORT_TTL_DAYS)
+                .execute(&pool)
+                .await,
+            );
+            if let Err(e) = result {
+                tracing::error!("Failed to clean up data exports: {:?}", e);
+            }
+        }
+    });
+}
@@ -0,0 +1,274 @@
+use axum::{
+    extract::{
+        rejection::{JsonRejection, PathRejection, QueryRejection},
+        FromRequest, FromRequestParts,
+    },
+    http::{header, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use validator::ValidationErrors;
+
+use crate::request_id;
+
+/// Satu tipe error untuk semua handler. Semua varian dirender ke JSON envelope yang sama:
+/// `{ "code", "message", "field_errors", "request_id" }`.
+#[derive(Debug)]
+pub enum AppError {
+    Unauthorized(String),
+    // Sudah login, tapi tidak boleh (mis. 2FA wajib belum aktif, bukan super admin)
+    Forbidden(String),
+    NotFound(String),
+    Conflict(String),
+    // 409 dari unique index slug (lihat SLUG_INDEXES): slug keburu dipakai transaksi lain, slug::finish mengulang
+    SlugTaken,
+    // If-Match tidak cocok dengan versi data sekarang
+    PreconditionFailed(String),
+    // 429 + header Retry-After (detik)
+    TooManyRequests { message: String, retry_after: u64 },
+    Validation(ValidationErrors),
+    // Rejection dari extractor axum (JSON rusak, content-type salah, tipe field salah, ...)
+    Rejected(StatusCode, String),
+    // Detail asli hanya masuk log, client cukup dapat pesan umum
+    Database(sqlx::Error),
+    // Error server lain (signing token, dsb). Pesan hanya masuk log
+    Internal(String),
+}
+
+#[derive(Debug, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ErrorBody {
+    pub code: &'static str,
+    pub message: String,
+    pub field_errors: Vec<FieldError>,
+    pub request_id: Option<String>,
+}
+
+impl AppError {
+    pub fn not_found(message: impl Into<String>) -> Self {
+        AppError::NotFound(message.into())
+    }
+
+    /// 409 dari unique index slug: slug yang dipilih keburu dipakai transaksi lain.
+    pub fn is_slug_conflict(&self) -> bool {
+        matches!(self, AppError::SlugTaken)
+    }
+
+    fn status(&self) -> StatusCode {
+        match self {
+            AppError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            AppError::Forbidden(_) => StatusCode::FORBIDDEN,
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Conflict(_) | AppError::SlugTaken => StatusCode::CONFLICT,
+            AppError::PreconditionFailed(_) => StatusCode::PRECONDITION_FAILED,
+            AppError::TooManyRequests { .. } => StatusCode::TOO_MANY_REQUESTS,
+            AppError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::Rejected(status, _) => *status,
+            AppError::Database(_) | AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn code(&self) -> &'static str {
+        match self {
+            AppError::Unauthorized(_) => "unauthorized",
+            AppError::Forbidden(_) => "forbidden",
+            AppError::NotFound(_) => "not_found",
+            AppError::Conflict(_) | AppError::SlugTaken => "conflict",
+            AppError::PreconditionFailed(_) => "precondition_failed",
+            AppError::TooManyRequests { .. } => "too_many_requests",
+            AppError::Validation(_) => "validation_failed",
+            AppError::Rejected(status, _) => match *status {
+                StatusCode::UNSUPPORTED_MEDIA_TYPE => "unsupported_media_type",
+                StatusCode::UNPROCESSABLE_ENTITY => "invalid_payload",
+                StatusCode::PAYLOAD_TOO_LARGE => "payload_too_large",
+                StatusCode::GONE => "gone",
+                _ => "bad_request",
+            },
+            AppError::Database(_) | AppError::Internal(_) => "internal_error",
+        }
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let status = self.status();
+        let code = self.code();
+        let request_id = request_id::current();
+        let retry_after = match &self {
+            AppError::TooManyRequests { retry_after, .. } => Some(*retry_after),
+            _ => None,
+        };
+
+        let (message, field_errors) = match self {
+            AppError::Unauthorized(m)
+            | AppError::Forbidden(m)
+            | AppError::NotFound(m)
+            | AppError::Conflict(m)
+            | AppError::PreconditionFailed(m)
+            | AppError::Rejected(_, m)
+            | AppError::TooManyRequests { message: m, .. } => (m, Vec::new()),
+            AppError::SlugTaken => ("Slug sudah dipakai".to_string(), Vec::new()),
+            AppError::Validation(errors) => ("Validasi gagal".to_string(), field_errors(&errors)),
+            // Level error hanya request id + jenis error. Detail sqlx (bisa berisi nilai parameter) hanya di level debug
+            AppError::Database(err) => {
+                let id = request_id.as_deref().unwrap_or("-");
+                let sqlstate = match &err {
+                    sqlx::Error::Database(db) => db.code().map(|code| code.to_string()),
+                    _ => None,
+                };
+                tracing::error!(request_id = id, kind = db_error_kind(&err), sqlstate, "database error");
+                tracing::debug!(request_id = id, error = ?err, "database error detail");
+                ("Terjadi kesalahan pada server".to_string(), Vec::new())
+            }
+            AppError::Internal(detail) => {
+                let id = request_id.as_deref().unwrap_or("-");
+                tracing::error!(request_id = id, kind = "internal", "internal error");
+                tracing::debug!(request_id = id, detail, "internal error detail");
+                ("Terjadi kesalahan pada server".to_string(), Vec::new())
+            }
+        };
+
+        let mut response = (status, Json(ErrorBody { code, message, field_errors, request_id })).into_response();
+        if let Some(seconds) = retry_after {
+            response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(seconds));
+        }
+        response
+    }
+}
+
+// Jenis error sqlx tanpa isi pesan / nilai parameter
+fn db_error_kind(err: &sqlx::Error) -> &'static str {
+    match err {
+        sqlx::Error::Database(_) => "database",
+        sqlx::Error::PoolTimedOut => "pool_timed_out",
+        sqlx::Error::PoolClosed => "pool_closed",
+        sqlx::Error::Io(_) => "io",
+        sqlx::Error::Tls(_) => "tls",
+        sqlx::Error::Protocol(_) => "protocol",
+        sqlx::Error::ColumnDecode { .. } | sqlx::Error::Decode(_) => "decode",
+        sqlx::Error::ColumnNotFound(_) | sqlx::Error::ColumnIndexOutOfBounds { .. } => "column",
+        sqlx::Error::TypeNotFound { .. } => "type_not_found",
+        sqlx::Error::RowNotFound => "row_not_found",
+        _ => "other",
+    }
+}
+
+impl From<sqlx::Error> for AppError {
+    fn from(err: sqlx::Error) -> Self {
+        match err {
+            sqlx::Error::RowNotFound => AppError::not_found("Data tidak ditemukan"),
+            // 23505 = unique_violation, 23503 = foreign_key_violation
+            sqlx::Error::Database(ref db) if db.code().as_deref() == Some("23505") => match db.constraint() {
+                Some(c) if SLUG_INDEXES.contains(&c) => AppError::SlugTaken,
+                constraint => AppError::Conflict(conflict_message(constraint).to_string()),
+            },
+            sqlx::Error::Database(ref db) if db.code().as_deref() == Some("23503") => {
+                AppError::Conflict("Data masih dipakai oleh data lain".to_string())
+            }
+            other => AppError::Database(other),
+        }
+    }
+}
+
+// Unique index slug per tabel (migrations/0009_slugs.sql)
+const SLUG_INDEXES: &[&str] = &[
+    "wisata_alam_slug_key",
+    "wisata_pendidikan_slug_key",
+    "tempat_nongkrong_slug_key",
+    "kuliner_slug_key",
+    "news_slug_key",
+];
+
+// Pesan 409 yang ramah tanpa membocorkan nama constraint
+fn conflict_message(constraint: Option<&str>) -> &'static str {
+    match constraint {
+        Some(c) if c.contains("username") => "Username sudah dipakai",
+        Some(c) if c.contains("email") => "Email sudah terdaftar",
+        _ => "Data sudah ada",
+    }
+}
+
+impl From<ValidationErrors> for AppError {
+    fn from(errors: ValidationErrors) -> Self {
+        AppError::Validation(errors)
+    }
+}
+
+// Status bawaan axum dipertahankan (400 JSON rusak, 415 content-type, 422 tipe field salah).
+// Teks rejection axum (bahasa Inggris) hanya masuk log debug, client dapat pesan bahasa Indonesia
+impl From<JsonRejection> for AppError {
+    fn from(rejection: JsonRejection) -> Self {
+        let message = match &rejection {
+            JsonRejection::MissingJsonContentType(_) => "Content-Type harus application/json",
+            JsonRejection::JsonSyntaxError(_) => "Body bukan JSON yang valid",
+            JsonRejection::JsonDataError(_) => "Isi JSON tidak sesuai format yang diharapkan",
+            _ => "Body request tidak bisa dibaca",
+        };
+        rejected(rejection.status(), rejection.body_text(), message)
+    }
+}
+
+impl From<PathRejection> for AppError {
+    fn from(rejection: PathRejection) -> Self {
+        rejected(rejection.status(), rejection.body_text(), "Parameter path tidak valid")
+    }
+}
+
+impl From<QueryRejection> for AppError {
+    fn from(rejection: QueryRejection) -> Self {
+        rejected(rejection.status(), rejection.body_text(), "Parameter query tidak valid")
+    }
+}
+
+fn rejected(status: StatusCode, detail: String, message: &str) -> AppError {
+    tracing::debug!(status = status.as_u16(), detail, "request rejected");
+    AppError::Rejected(status, message.to_string())
+}
+
+// Flatten ValidationErrors (termasuk nested struct/list) ke daftar field -> pesan
+fn field_errors(errors: &ValidationErrors) -> Vec<FieldError> {
+    let mut out = Vec::new();
+    collect_field_errors("", errors, &mut out);
+    out
+}
+
+fn collect_field_errors(prefix: &str, errors: &ValidationErrors, out: &mut Vec<FieldError>) {
+    use validator::ValidationErrorsKind;
+
+    for (field, kind) in errors.errors() {
+        let path = if prefix.is_empty() { field.to_string() } else { format!("{prefix}.{field}") };
+        match kind {
+            ValidationErrorsKind::Field(list) => out.extend(list.iter().map(|e| FieldError {
+                field: path.clone(),
+                message: e.message.as_ref().map(|m| m.to_string()).unwrap_or_else(|| e.code.to_string()),
+            })),
+            ValidationErrorsKind::Struct(inner) => collect_field_errors(&path, inner, out),
+            ValidationErrorsKind::List(items) => {
+                for (i, inner) in items {
+                    collect_field_errors(&format!("{path}[{i}]"), inner, out);
+                }
+            }
+        }
+    }
+}
+
+pub type AppResult<T> = Result<T, AppError>;
+
+// Extractor pengganti Json/Path/Query bawaan axum supaya rejection-nya juga pakai envelope
+#[derive(FromRequest)]
+#[from_request(via(axum::Json), rejection(AppError))]
+pub struct AppJson<T>(pub T);
+
+#[derive(FromRequestParts)]
+#[from_request(via(axum::extract::Path), rejection(AppError))]
+pub struct AppPath<T>(pub T);
+
+#[derive(FromRequestParts)]
+#[from_request(via(axum::extract::Query), rejection(AppError))]
+pub struct AppQuery<T>(pub T);
@@ -0,0 +1,74 @@
+use axum::{
+    extract::FromRequestParts,
+    http::{header, request::Parts, HeaderValue},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use sqlx::PgConnection;
+
+use crate::error::AppError;
+
+/// ETag dari kolom `version`: "3" (strong).
+pub fn etag(version: i32) -> HeaderValue {
+    HeaderValue::from_str(&format!("\"{version}\"")).expect("angka selalu valid di header")
+}
+
+/// Response JSON + header ETag.
+pub fn tagged<T: Serialize>(version: i32, body: T) -> Response {
+    ([(header::ETAG, etag(version))], Json(body)).into_response()
+}
+
+/// Header If-Match opsional. `None` = tidak dikirim atau `*` (tulis tanpa cek versi).
+/// Nilai yang tidak bisa dibaca dianggap tidak cocok dengan versi mana pun.
+pub struct IfMatch(pub Option<i32>);
+
+impl<S: Send + Sync> FromRequestParts<S> for IfMatch {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let Some(value) = parts.headers.get(header::IF_MATCH) else {
+            return Ok(IfMatch(None));
+        };
+
+        let value = value.to_str().unwrap_or_default().trim();
+        if value == "*" {
+            return Ok(IfMatch(None));
+        }
+
+        // Weak tag (W/"3") tetap diterima, browser/proxy kadang menambahkannya
+        value
+            .trim_start_matches("W/")
+            .trim_matches('"')
+            .parse()
+            .map(|v| IfMatch(Some(v)))
+            .map_err(|_| stale())
+    }
+}
+
+fn stale() -> AppError {
+    AppError::PreconditionFailed("Data sudah diubah orang lain, muat ulang dulu".to_string())
+}
+
+/// Cek If-Match sebelum menulis: 404 kalau id tidak ada, 412 kalau versinya sudah basi.
+pub async fn check(conn: &mut PgConnection, table: &'static str, id: i32, expected: Option<i32>) -> Result<(), AppError> {
+    let current: Option<i32> = sqlx::query_scalar(&format!("SELECT version FROM {table} WHERE id = $1 AND deleted_at IS NULL"))
+        .bind(id)
+        .fetch_optional(conn)
+        .await?;
+
+    match (current, expected) {
+        (None, _) => Err(AppError::not_found("ID tidak ditemukan")),
+        (Some(current), Some(expected)) if current != expected => Err(stale()),
+        _ => Ok(()),
+    }
+}
+
+/// UPDATE ... WHERE version = $n tidak mengembalikan baris: bedakan 404 dan 412
+/// (ada admin lain yang menulis di antara cek dan update).
+pub async fn missing_or_stale(conn: &mut PgConnection, table: &'static str, id: i32) -> AppError {
+    match check(conn, table, id, None).await {
+        Ok(()) => stale(),
+        Err(err) => err,
+    }
+}
@@ -0,0 +1,259 @@
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::app_state::AppState;
+use crate::error::{AppQuery, AppResult};
+use crate::markdown;
+use crate::news::NewsItem;
+
+// Jumlah berita terbaru yang dimasukkan ke feed
+const FEED_LIMIT: i64 = 50;
+const SUMMARY_WORDS: usize = 40;
+// Feed reader biasanya polling tiap 15-60 menit
+const CACHE_CONTROL: &str = "public, max-age=900";
+
+const FEED_TITLE: &str = "ExploreMas - Kabar Banyumas";
+const FEED_DESCRIPTION: &str = "Informasi terkini seputar wisata dan event lokal Banyumas.";
+
+// ?category=... untuk feed per kategori
+#[derive(Debug, Deserialize)]
+pub struct FeedQuery {
+    pub category: Option<String>,
+}
+
+// GET /api/news/rss.xml
+pub async fn news_rss(
+    State(state): State<AppState>,
+    AppQuery(query): AppQuery<FeedQuery>,
+    headers: HeaderMap,
+) -> AppResult<Response> {
+    let items = fetch_feed_items(&state, query.category.as_deref()).await?;
+    let body = render_rss(&state.frontend_url, &state.api_url, query.category.as_deref(), &items);
+
+    Ok(feed_response(&headers, "application/rss+xml; charset=utf-8", body, &items))
+}
+
+// GET /api/news/atom.xml
+pub async fn news_atom(
+    State(state): State<AppState>,
+    AppQuery(query): AppQuery<FeedQuery>,
+    headers: HeaderMap,
+) -> AppResult<Response> {
+    let items = fetch_feed_items(&state, query.category.as_deref()).await?;
+    let body = render_atom(&state.frontend_url, &state.api_url, query.category.as_deref(), &items);
+
+    Ok(feed_response(&headers, "application/atom+xml; charset=utf-8", body, &items))
+}
+
+async fn fetch_feed_items(
+    state: &AppState,
+    category: Option<&str>,
+) -> AppResult<Vec<NewsItem>> {
+    let items = sqlx::query_as::<_, NewsItem>(
+        r#"
+        SELECT id, slug, title, category, image_url, content, COALESCE(content_html, '') AS content_html,
+               published_at, read_minutes
+        FROM news
+        WHERE deleted_at IS NULL
+          AND ($1::text IS NULL OR category = $1)
+        ORDER BY published_at DESC, id DESC
+        LIMIT $2
+        "#,
+    )
+    .bind(category)
+    .bind(FEED_LIMIT)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(items)
+}
+
+// Pasang header cache (ETag, Last-Modified) dan balas 304 kalau feed belum berubah
+fn feed_response(request: &HeaderMap, content_type: &'static str, body: String, items: &[NewsItem]) -> Response {
+    // Hash isi feed (stabil antar versi Rust / restart, beda dengan DefaultHasher), 128 bit pertama sudah cukup
+    let digest = format!("{:x}", Sha256::digest(body.as_bytes()));
+    let etag = format!("\"{}\"", &digest[..32]);
+    let last_modified = items.iter().map(|n| n.published_at).max().unwrap_or_else(Utc::now);
+
+    let not_modified = match request.get(header::IF_NONE_MATCH) {
+        Some(value) => value.to_str().is_ok_and(|v| v.split(',').any(|t| t.trim() == etag || t.trim() == "*")),
+        None => request
+            .get(header::IF_MODIFIED_SINCE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| DateTime::parse_from_rfc2822(v).ok())
+            .is_some_and(|since| last_modified.timestamp() <= since.timestamp()),
+    };
+
+    let mut headers = HeaderMap::new();
+    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static(CACHE_CONTROL));
+    if let Ok(value) = HeaderValue::from_str(&etag) {
+        headers.insert(header::ETAG, value);
+    }
+    if let Ok(value) = HeaderValue::from_str(&http_date(last_modified)) {
+        headers.insert(header::LAST_MODIFIED, value);
+    }
+
+    if not_modified {
+        return (StatusCode::NOT_MODIFIED, headers).into_response();
+    }
+
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+    (headers, body).into_response()
+}
+
+fn render_rss(base_url: &str, api_url: &str, category: Option<&str>, items: &[NewsItem]) -> String {
+    let channel_title = feed_title(category);
+    let self_link = feed_link(api_url, "rss.xml", category);
+    let last_build = items.first().map(|n| n.published_at).unwrap_or_else(Utc::now);
+
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<rss version=\"2.0\" xmlns:atom=\"http://www.w3.org/2005/Atom\">\n<channel>\n");
+    xml.push_str(&format!("  <title>{}</title>\n", escape(&channel_title)));
+    xml.push_str(&format!("  <link>{}</link>\n", escape(base_url)));
+    xml.push_str(&format!("  <description>{}</description>\n", escape(FEED_DESCRIPTION)));
+    xml.push_str("  <language>id</language>\n");
+    xml.push_str(&format!("  <lastBuildDate>{}</lastBuildDate>\n", last_build.to_rfc2822()));
+    xml.push_str(&format!(
+        "  <atom:link href=\"{}\" rel=\"self\" type=\"application/rss+xml\"/>\n",
+        escape(&self_link)
+    ));
+
+    for item in items {
+        let link = article_link(base_url, item);
+        xml.push_str("  <item>\n");
+        xml.push_str(&format!("    <title>{}</title>\n", escape(&item.title)));
+        xml.push_str(&format!("    <link>{}</link>\n", escape(&link)));
+        xml.push_str(&format!("    <guid isPermaLink=\"true\">{}</guid>\n", escape(&link)));
+        xml.push_str(&format!("    <category>{}</category>\n", escape(&item.category)));
+        xml.push_str(&format!("    <pubDate>{}</pubDate>\n", item.published_at.to_rfc2822()));
+        xml.push_str(&format!("    <description>{}</description>\n", escape(&summary(&item.content))));
+        if !item.image_url.is_empty() {
+            xml.push_str(&format!(
+                "    <enclosure url=\"{}\" length=\"0\" type=\"{}\"/>\n",
+                escape(&item.image_url),
+                image_mime(&item.image_url)
+            ));
+        }
+        xml.push_str("  </item>\n");
+    }
+
+    xml.push_str("</channel>\n</rss>\n");
+    xml
+}
+
+fn render_atom(base_url: &str, api_url: &str, category: Option<&str>, items: &[NewsItem]) -> String {
+    let self_link = feed_link(api_url, "atom.xml", category);
+    let updated = items.first().map(|n| n.published_at).unwrap_or_else(Utc::now);
+
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\" xml:lang=\"id\">\n");
+    xml.push_str(&format!("  <title>{}</title>\n", escape(&feed_title(category))));
+    xml.push_str(&format!("  <subtitle>{}</subtitle>\n", escape(FEED_DESCRIPTION)));
+    xml.push_str(&format!("  <id>{}</id>\n", escape(&self_link)));
+    xml.push_str(&format!("  <link href=\"{}\"/>\n", escape(base_url)));
+    xml.push_str(&format!("  <link rel=\"self\" href=\"{}\"/>\n", escape(&self_link)));
+    xml.push_str(&format!("  <updated>{}</updated>\n", updated.to_rfc3339()));
+    xml.push_str("  <author><name>ExploreMas</name></author>\n");
+
+    for item in items {
+        let link = article_link(base_url, item);
+        xml.push_str("  <entry>\n");
+        xml.push_str(&format!("    <title>{}</title>\n", escape(&item.title)));
+        xml.push_str(&format!("    <id>{}</id>\n", escape(&link)));
+        xml.push_str(&format!("    <link href=\"{}\"/>\n", escape(&link)));
+        xml.push_str(&format!("    <published>{}</published>\n", item.published_at.to_rfc3339()));
+        xml.push_str(&format!("    <updated>{}</updated>\n", item.published_at.to_rfc3339()));
+        xml.push_str(&format!("    <category term=\"{}\"/>\n", escape(&item.category)));
+        xml.push_str(&format!("    <summary>{}</summary>\n", escape(&summary(&item.content))));
+        if !item.image_url.is_empty() {
+            xml.push_str(&format!(
+                "    <link rel=\"enclosure\" href=\"{}\" type=\"{}\"/>\n",
+                escape(&item.image_url),
+                image_mime(&item.image_url)
+            ));
+        }
+        xml.push_str("  </entry>\n");
+    }
+
+    xml.push_str("</feed>\n");
+    xml
+}
+
+fn feed_title(category: Option<&str>) -> String {
+    match category {
+        Some(c) => format!("{FEED_TITLE} ({c})"),
+        None => FEED_TITLE.to_string(),
+    }
+}
+
+fn feed_link(api_url: &str, file: &str, category: Option<&str>) -> String {
+    match category {
+        Some(c) => format!("{api_url}/api/news/{file}?category={}", encode_query(c)),
+        None => format!("{api_url}/api/news/{file}"),
+    }
+}
+
+fn article_link(base_url: &str, item: &NewsItem) -> String {
+    format!("{base_url}/news/{}", item.slug)
+}
+
+// Ringkasan = beberapa kata pertama dari isi berita (tanpa sintaks Markdown)
+fn summary(content: &str) -> String {
+    let text = markdown::to_plain_text(content);
+    let words: Vec<&str> = text.split_whitespace().collect();
+    if words.len() <= SUMMARY_WORDS {
+        words.join(" ")
+    } else {
+        format!("{}…", words[..SUMMARY_WORDS].join(" "))
+    }
+}
+
+fn image_mime(url: &str) -> &'static str {
+    let path = url.split(['?', '#']).next().unwrap_or(url).to_ascii_lowercase();
+    if path.ends_with(".png") {
+        "image/png"
+    } else if path.ends_with(".webp") {
+        "image/webp"
+    } else if path.ends_with(".gif") {
+        "image/gif"
+    } else {
+        "image/jpeg"
+    }
+}
+
+fn http_date(ts: DateTime<Utc>) -> String {
+    ts.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+fn encode_query(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+pub fn escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
@@ -0,0 +1,33 @@
+use axum::http::{HeaderMap, HeaderName, HeaderValue};
+
+// Nama header buatan sendiri, dipakai bersama oleh modul fitur dan cors.rs (expose_headers).
+// Header respons yang perlu dibaca frontend harus ikut di-expose di sana
+
+/// Id request, dari client atau dibuat request_id_middleware.
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Key partner (api_key_middleware).
+pub const API_KEY_HEADER: HeaderName = HeaderName::from_static("x-api-key");
+
+/// Respons /login: supaya frontend bisa menandai akun yang emailnya belum diverifikasi.
+pub const EMAIL_VERIFIED_HEADER: HeaderName = HeaderName::from_static("x-email-verified");
+
+// Header rate limit standar (draft IETF "RateLimit header fields")
+pub const RATELIMIT_LIMIT: HeaderName = HeaderName::from_static("ratelimit-limit");
+pub const RATELIMIT_REMAINING: HeaderName = HeaderName::from_static("ratelimit-remaining");
+pub const RATELIMIT_RESET: HeaderName = HeaderName::from_static("ratelimit-reset");
+pub const RATELIMIT_POLICY: HeaderName = HeaderName::from_static("ratelimit-policy");
+
+// Metadata halaman untuk list yang di-paging (news, audit)
+pub const TOTAL_COUNT_HEADER: HeaderName = HeaderName::from_static("x-total-count");
+pub const PAGE_HEADER: HeaderName = HeaderName::from_static("x-page");
+pub const PER_PAGE_HEADER: HeaderName = HeaderName::from_static("x-per-page");
+
+/// X-Total-Count, X-Page dan X-Per-Page untuk satu halaman list.
+pub fn paging(total: i64, page: i64, per_page: i64) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(TOTAL_COUNT_HEADER, HeaderValue::from(total));
+    headers.insert(PAGE_HEADER, HeaderValue::from(page));
+    headers.insert(PER_PAGE_HEADER, HeaderValue::from(per_page));
+    headers
+}
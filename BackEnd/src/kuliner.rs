@@ -1,129 +1,275 @@
 use axum::{
-    extract::{Path, State},
+    extract::State,
     http::StatusCode,
+    response::{IntoResponse, Redirect, Response},
     Json,
 };
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use validator::Validate;
 
 use crate::app_state::AppState;
+use crate::error::{AppError, AppPath, AppResult};
+use crate::auth::AdminUser;
+use crate::etag::{self, IfMatch};
+use crate::revision::{self, Action};
+use crate::validation::{validate_image_url, validate_maps_url, validate_not_blank, ValidJson};
+use crate::slug;
+
+const TABLE: &str = "kuliner";
+const COLUMNS: &str = "id, slug, nama_tempat, kategori, alamat, htm, link_gmaps, link_foto, version";
 
 #[derive(Debug, Serialize, FromRow)]
 pub struct Kuliner {
     pub id: i32,
+    pub slug: String,
     pub nama_tempat: String,
     pub kategori: String,
     pub alamat: String,
     pub htm: i32,
     pub link_gmaps: String,
     pub link_foto: String,
+    pub version: i32,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Validate)]
 pub struct KulinerPayload {
+    #[validate(length(min = 1, max = 120, message = "nama_tempat harus 1-120 karakter"), custom(function = "validate_not_blank"))]
     pub nama_tempat: String,
+    #[validate(length(min = 1, max = 50, message = "kategori harus 1-50 karakter"), custom(function = "validate_not_blank"))]
     pub kategori: String,
+    #[validate(length(min = 1, max = 300, message = "alamat harus 1-300 karakter"), custom(function = "validate_not_blank"))]
     pub alamat: String,
+    #[validate(range(min = 0, max = 10_000_000, message = "htm harus antara 0 dan 10000000"))]
     pub htm: i32,
+    #[validate(length(max = 500, message = "maksimal 500 karakter"), custom(function = "validate_maps_url"))]
     pub link_gmaps: String,
+    #[validate(length(max = 500, message = "maksimal 500 karakter"), custom(function = "validate_image_url"))]
     pub link_foto: String,
 }
 
-pub async fn get_kuliner(State(state): State<AppState>) -> Result<Json<Vec<Kuliner>>, (StatusCode, String)> {
+// Body PATCH: field yang tidak dikirim tidak diubah
+#[derive(Debug, Deserialize, Validate)]
+pub struct KulinerPatch {
+    #[validate(length(min = 1, max = 120, message = "nama_tempat harus 1-120 karakter"), custom(function = "validate_not_blank"))]
+    pub nama_tempat: Option<String>,
+    #[validate(length(min = 1, max = 50, message = "kategori harus 1-50 karakter"), custom(function = "validate_not_blank"))]
+    pub kategori: Option<String>,
+    #[validate(length(min = 1, max = 300, message = "alamat harus 1-300 karakter"), custom(function = "validate_not_blank"))]
+    pub alamat: Option<String>,
+    #[validate(range(min = 0, max = 10_000_000, message = "htm harus antara 0 dan 10000000"))]
+    pub htm: Option<i32>,
+    #[validate(length(max = 500, message = "maksimal 500 karakter"), custom(function = "validate_maps_url"))]
+    pub link_gmaps: Option<String>,
+    #[validate(length(max = 500, message = "maksimal 500 karakter"), custom(function = "validate_image_url"))]
+    pub link_foto: Option<String>,
+}
+
+pub async fn get_kuliner(State(state): State<AppState>) -> AppResult<Json<Vec<Kuliner>>> {
     let rows = sqlx::query_as::<_, Kuliner>(
-        r#"SELECT id, nama_tempat, kategori, alamat, htm, link_gmaps, link_foto FROM kuliner ORDER BY id"#,
+        &format!("SELECT {COLUMNS} FROM kuliner WHERE deleted_at IS NULL ORDER BY id"),
     )
     .fetch_all(&state.pool)
-    .await
-    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("DB error: {e:?}")))?;
+    .await?;
 
     Ok(Json(rows))
 }
 
 pub async fn get_kuliner_id(
     State(state): State<AppState>,
-    Path(id): Path<i32>,
-) -> Result<Json<Kuliner>, (StatusCode, String)> {
+    AppPath(id): AppPath<i32>,
+) -> AppResult<Response> {
     let row = sqlx::query_as::<_, Kuliner>(
-        r#"SELECT id, nama_tempat, kategori, alamat, htm, link_gmaps, link_foto FROM kuliner WHERE id = $1"#,
+        &format!("SELECT {COLUMNS} FROM kuliner WHERE id = $1 AND deleted_at IS NULL"),
     )
     .bind(id)
     .fetch_one(&state.pool)
-    .await
-    .map_err(|e| (StatusCode::NOT_FOUND, format!("Not found / DB error: {e:?}")))?;
+    .await?;
 
-    Ok(Json(row))
+    Ok(etag::tagged(row.version, row))
 }
 
-pub async fn create_kuliner(
+// GET /kuliner/slug/{slug} -> slug lama di-redirect ke slug terbaru
+pub async fn get_kuliner_by_slug(
     State(state): State<AppState>,
-    Json(payload): Json<KulinerPayload>,
-) -> Result<(StatusCode, Json<Kuliner>), (StatusCode, String)> {
-    let inserted = sqlx::query_as::<_, Kuliner>(
-        r#"
-        INSERT INTO kuliner (nama_tempat, kategori, alamat, htm, link_gmaps, link_foto)
-        VALUES ($1,$2,$3,$4,$5,$6)
-        RETURNING id, nama_tempat, kategori, alamat, htm, link_gmaps, link_foto
-        "#,
+    AppPath(slug_param): AppPath<String>,
+) -> AppResult<Response> {
+    let row = sqlx::query_as::<_, Kuliner>(
+        &format!("SELECT {COLUMNS} FROM kuliner WHERE slug = $1 AND deleted_at IS NULL"),
     )
-    .bind(payload.nama_tempat)
-    .bind(payload.kategori)
-    .bind(payload.alamat)
-    .bind(payload.htm)
-    .bind(payload.link_gmaps)
-    .bind(payload.link_foto)
-    .fetch_one(&state.pool)
-    .await
-    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("DB error: {e:?}")))?;
+    .bind(&slug_param)
+    .fetch_optional(&state.pool)
+    .await?;
+
+    if let Some(row) = row {
+        return Ok(etag::tagged(row.version, row));
+    }
+
+    match slug::find_redirect(&state.pool, TABLE, &slug_param).await? {
+        Some(current) => Ok(Redirect::permanent(&format!("/kuliner/slug/{current}")).into_response()),
+        None => Err(AppError::not_found("Slug tidak ditemukan")),
+    }
+}
+
+pub async fn create_kuliner(
+    State(state): State<AppState>,
+    admin: AdminUser,
+    ValidJson(payload): ValidJson<KulinerPayload>,
+) -> AppResult<(StatusCode, Json<Kuliner>)> {
+    // Slug, INSERT dan revisi dalam satu transaksi, diulang kalau slug keburu dipakai request lain
+    let mut attempt = 1;
+    let inserted = loop {
+        let mut tx = state.pool.begin().await?;
+        let result = async {
+            let new_slug = slug::unique_slug(&mut tx, TABLE, &payload.nama_tempat, None).await?;
+
+            let inserted = sqlx::query_as::<_, Kuliner>(&format!(
+                r#"
+                INSERT INTO kuliner (nama_tempat, kategori, alamat, htm, link_gmaps, link_foto, slug)
+                VALUES ($1,$2,$3,$4,$5,$6,$7)
+                RETURNING {COLUMNS}
+                "#,
+            ))
+            .bind(&payload.nama_tempat)
+            .bind(&payload.kategori)
+            .bind(&payload.alamat)
+            .bind(payload.htm)
+            .bind(&payload.link_gmaps)
+            .bind(&payload.link_foto)
+            .bind(&new_slug)
+            .fetch_one(&mut *tx)
+            .await?;
 
+            revision::record(&mut tx, TABLE, inserted.id, Action::Create, &admin, None).await?;
+            Ok(inserted)
+        }
+        .await;
+        if let Some(inserted) = slug::finish(tx, result, &mut attempt).await? {
+            break inserted;
+        }
+    };
+
+    state.sitemap.invalidate();
     Ok((StatusCode::CREATED, Json(inserted)))
 }
 
 // --- BARU: UPDATE KULINER ---
+// PUT = payload lengkap. If-Match opsional, kalau dikirim harus sama dengan ETag terakhir.
 pub async fn update_kuliner(
     State(state): State<AppState>,
-    Path(id): Path<i32>,
-    Json(payload): Json<KulinerPayload>,
-) -> Result<Json<String>, (StatusCode, String)> {
-    let result = sqlx::query(
-        r#"
-        UPDATE kuliner 
-        SET nama_tempat=$1, kategori=$2, alamat=$3, htm=$4, link_gmaps=$5, link_foto=$6 
-        WHERE id=$7
-        "#
-    )
-    .bind(payload.nama_tempat)
-    .bind(payload.kategori)
-    .bind(payload.alamat)
-    .bind(payload.htm)
-    .bind(payload.link_gmaps)
-    .bind(payload.link_foto)
-    .bind(id)
-    .execute(&state.pool)
-    .await
-    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("DB Update Error: {e:?}")))?;
+    admin: AdminUser,
+    AppPath(id): AppPath<i32>,
+    IfMatch(expected): IfMatch,
+    ValidJson(payload): ValidJson<KulinerPayload>,
+) -> AppResult<Response> {
+    let updated = save_kuliner(&state, &admin, id, payload, expected).await?;
+    Ok(etag::tagged(updated.version, updated))
+}
 
-    if result.rows_affected() == 0 {
-        return Err((StatusCode::NOT_FOUND, "ID tidak ditemukan".to_string()));
-    }
+// PATCH = hanya field yang berubah, sisanya diambil dari data sekarang.
+// Tanpa If-Match tetap dikunci ke versi yang dibaca, supaya perubahan admin lain di antaranya tidak tertimpa
+pub async fn patch_kuliner(
+    State(state): State<AppState>,
+    admin: AdminUser,
+    AppPath(id): AppPath<i32>,
+    IfMatch(expected): IfMatch,
+    ValidJson(patch): ValidJson<KulinerPatch>,
+) -> AppResult<Response> {
+    let current = sqlx::query_as::<_, Kuliner>(&format!("SELECT {COLUMNS} FROM kuliner WHERE id = $1 AND deleted_at IS NULL"))
+        .bind(id)
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or_else(|| AppError::not_found("ID tidak ditemukan"))?;
+
+    let payload = KulinerPayload {
+        nama_tempat: patch.nama_tempat.unwrap_or(current.nama_tempat),
+        kategori: patch.kategori.unwrap_or(current.kategori),
+        alamat: patch.alamat.unwrap_or(current.alamat),
+        htm: patch.htm.unwrap_or(current.htm),
+        link_gmaps: patch.link_gmaps.unwrap_or(current.link_gmaps),
+        link_foto: patch.link_foto.unwrap_or(current.link_foto),
+    };
+
+    let updated = save_kuliner(&state, &admin, id, payload, expected.or(Some(current.version))).await?;
+    Ok(etag::tagged(updated.version, updated))
+}
+
+async fn save_kuliner(
+    state: &AppState,
+    admin: &AdminUser,
+    id: i32,
+    payload: KulinerPayload,
+    expected: Option<i32>,
+) -> AppResult<Kuliner> {
+    // Rename -> slug baru, slug lama tetap jalan lewat redirect. Cek versi, slug, redirect, UPDATE dan revisi
+    // satu transaksi: kalau UPDATE gagal (412 / 404) redirect dan revisi ikut batal
+    let mut attempt = 1;
+    let updated = loop {
+        let mut tx = state.pool.begin().await?;
+        let result = async {
+            let before = revision::snapshot(&mut tx, TABLE, id).await?;
+            etag::check(&mut tx, TABLE, id, expected).await?;
+
+            let new_slug = slug::slug_for_update(&mut tx, TABLE, id, &payload.nama_tempat)
+                .await?
+                .ok_or_else(|| AppError::not_found("ID tidak ditemukan"))?;
+
+            let updated = sqlx::query_as::<_, Kuliner>(&format!(
+                r#"
+                UPDATE kuliner 
+                SET nama_tempat=$1, kategori=$2, alamat=$3, htm=$4, link_gmaps=$5, link_foto=$6, slug=$8, updated_at=now(), version=version+1 
+                WHERE id=$7 AND deleted_at IS NULL AND ($9::int IS NULL OR version=$9)
+                RETURNING {COLUMNS}
+                "#
+            ))
+            .bind(&payload.nama_tempat)
+            .bind(&payload.kategori)
+            .bind(&payload.alamat)
+            .bind(payload.htm)
+            .bind(&payload.link_gmaps)
+            .bind(&payload.link_foto)
+            .bind(id)
+            .bind(&new_slug)
+            .bind(expected)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+            let Some(updated) = updated else {
+                return Err(etag::missing_or_stale(&mut tx, TABLE, id).await);
+            };
+            revision::record(&mut tx, TABLE, id, Action::Update, admin, before).await?;
+            Ok(updated)
+        }
+        .await;
+        if let Some(updated) = slug::finish(tx, result, &mut attempt).await? {
+            break updated;
+        }
+    };
 
-    Ok(Json("Update Berhasil".to_string()))
+    state.sitemap.invalidate();
+    Ok(updated)
 }
 
 // --- BARU: DELETE KULINER ---
 pub async fn delete_kuliner(
     State(state): State<AppState>,
-    Path(id): Path<i32>,
-) -> Result<Json<String>, (StatusCode, String)> {
-    let result = sqlx::query("DELETE FROM kuliner WHERE id = $1")
+    admin: AdminUser,
+    AppPath(id): AppPath<i32>,
+) -> AppResult<StatusCode> {
+    // Snapshot, soft delete dan revisi satu transaksi
+    let mut tx = state.pool.begin().await?;
+    let before = revision::snapshot(&mut tx, TABLE, id).await?;
+    let result = sqlx::query("UPDATE kuliner SET deleted_at = now() WHERE id = $1 AND deleted_at IS NULL")
         .bind(id)
-        .execute(&state.pool)
-        .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("DB Delete Error: {e:?}")))?;
+        .execute(&mut *tx)
+        .await?;
 
     if result.rows_affected() == 0 {
-        return Err((StatusCode::NOT_FOUND, "ID tidak ditemukan".to_string()));
+        return Err(AppError::not_found("ID tidak ditemukan"));
     }
 
-    Ok(Json("Delete Berhasil".to_string()))
+    revision::record(&mut tx, TABLE, id, Action::Delete, &admin, before).await?;
+    tx.commit().await?;
+    state.sitemap.invalidate();
+    Ok(StatusCode::NO_CONTENT)
 }
\ No newline at end of file
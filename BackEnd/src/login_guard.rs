@@ -0,0 +1,121 @@
+use sqlx::PgPool;
+
+use crate::error::{AppError, AppResult};
+
+// Gagal sebanyak ini masih boleh langsung coba lagi, setelahnya mulai ditahan
+const ACCOUNT_FREE_ATTEMPTS: i32 = 3;
+// Per IP lebih longgar (satu IP bisa dipakai banyak orang lewat NAT)
+const IP_FREE_ATTEMPTS: i32 = 20;
+// Jeda pertama 2 detik, dua kali lipat tiap gagal, maksimal 15 menit (lockout sementara)
+const BASE_DELAY_SECS: i64 = 2;
+const MAX_DELAY_SECS: i64 = 15 * 60;
+// Hitungan gagal mulai dari nol lagi kalau sudah sejam tidak ada percobaan gagal
+const RESET_AFTER_SECS: f64 = 60.0 * 60.0;
+
+/// Scope hitungan gagal untuk password akun (login user maupun admin, konfirmasi password di /api/me).
+pub const LOGIN_SCOPE: &str = "account";
+
+/// Pesan yang sama untuk username tidak ada dan password salah.
+pub const INVALID_CREDENTIALS: &str = "Username atau password salah";
+
+pub fn invalid_credentials() -> AppError {
+    AppError::Unauthorized(INVALID_CREDENTIALS.to_string())
+}
+
+fn keys(scope: &str, username: &str, ip: Option<&str>) -> Vec<String> {
+    let mut keys = vec![format!("{scope}:{}", username.trim().to_lowercase())];
+    if let Some(ip) = ip {
+        keys.push(format!("ip:{ip}"));
+    }
+    keys
+}
+
+/// Ambil satu jatah percobaan untuk akun dan IP sebelum password / kode diverifikasi.
+/// Cek lockout dan penambahan hitungan terjadi dalam satu upsert per kunci, jadi request paralel tidak bisa
+/// lolos bersamaan dengan hitungan yang sama. Percobaan dihitung gagal sampai `attempt_succeeded` dipanggil.
+/// Kalau salah satu kunci sedang ditahan -> 429 + Retry-After, tidak ada hitungan yang bertambah.
+pub async fn begin_attempt(pool: &PgPool, scope: &str, username: &str, ip: Option<&str>) -> AppResult<()> {
+    let keys = keys(scope, username, ip);
+    let mut tx = pool.begin().await?;
+
+    for key in &keys {
+        let free = if key.starts_with("ip:") { IP_FREE_ATTEMPTS } else { ACCOUNT_FREE_ATTEMPTS };
+        // Hitungan baru = lama + 1 (atau 1 kalau sudah lewat RESET_AFTER_SECS). Lewat jatah gratis -> ditahan
+        // BASE_DELAY_SECS * 2^(lebih - 1), maksimal MAX_DELAY_SECS. Baris yang sedang ditahan tidak diubah
+        let counted: Option<i32> = sqlx::query_scalar(
+            "INSERT INTO login_throttle (key, failures, last_failure_at) VALUES ($1, 1, now())
+             ON CONFLICT (key) DO UPDATE SET
+                failures = CASE WHEN login_throttle.last_failure_at < now() - make_interval(secs => $2)
+                                THEN 1 ELSE login_throttle.failures + 1 END,
+                last_failure_at = now(),
+                locked_until = CASE
+                    WHEN login_throttle.last_failure_at >= now() - make_interval(secs => $2)
+                         AND login_throttle.failures >= $3
+                    THEN now() + make_interval(secs => LEAST($4 * power(2, LEAST(login_throttle.failures - $3, 20)), $5))
+                END
+             WHERE login_throttle.locked_until IS NULL OR login_throttle.locked_until <= now()
+             RETURNING failures",
+        )
+        .bind(key)
+        .bind(RESET_AFTER_SECS)
+        .bind(free)
+        .bind(BASE_DELAY_SECS as f64)
+        .bind(MAX_DELAY_SECS as f64)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if counted.is_none() {
+            tx.rollback().await?;
+            return Err(locked(pool, &keys).await?);
+        }
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+async fn locked(pool: &PgPool, keys: &[String]) -> AppResult<AppError> {
+    let remaining: Option<f64> = sqlx::query_scalar(
+        "SELECT EXTRACT(EPOCH FROM MAX(locked_until) - now())::float8
+         FROM login_throttle WHERE key = ANY($1) AND locked_until > now()",
+    )
+    .bind(keys)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(AppError::TooManyRequests {
+        message: "Terlalu banyak percobaan login, coba lagi nanti".to_string(),
+        retry_after: remaining.unwrap_or(1.0).ceil().max(1.0) as u64,
+    })
+}
+
+/// Percobaan dari `begin_attempt` berhasil: hitungan akun di-reset dan hitungan IP dikurangi satu
+/// (percobaan ini tidak jadi dihitung gagal), supaya login yang benar dari IP bersama (NAT) tidak ikut menahan IP itu.
+/// Kalau sisa hitungan IP tidak lagi melewati jatah gratis (`IP_FREE_ATTEMPTS`), tahanan IP dilepas.
+pub async fn attempt_succeeded(pool: &PgPool, scope: &str, username: &str, ip: Option<&str>) -> Result<(), sqlx::Error> {
+    record_success(pool, scope, username).await?;
+    if let Some(ip) = ip {
+        // Ruas kanan SET memakai nilai lama: failures - 1 = hitungan baru
+        sqlx::query(
+            "UPDATE login_throttle SET
+                failures = GREATEST(failures - 1, 0),
+                locked_until = CASE WHEN failures - 1 <= $2 THEN NULL ELSE locked_until END
+             WHERE key = $1",
+        )
+        .bind(format!("ip:{ip}"))
+        .bind(IP_FREE_ATTEMPTS)
+        .execute(pool)
+        .await?;
+    }
+    Ok(())
+}
+
+/// Hitungan gagal akun di-reset (login berhasil, password diganti / di-reset). Hitungan IP sengaja tidak di-reset,
+/// supaya satu akun valid tidak bisa dipakai untuk "membersihkan" IP yang sedang menebak password akun lain.
+pub async fn record_success(pool: &PgPool, scope: &str, username: &str) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM login_throttle WHERE key = $1")
+        .bind(format!("{scope}:{}", username.trim().to_lowercase()))
+        .execute(pool)
+        .await?;
+    Ok(())
+}
@@ -1,13 +1,37 @@
 use axum::{
-    http::Method,
+    middleware,
     routing::{get, post, put, delete},
     Router,
 };
-use tower_http::cors::{Any, CorsLayer};
+use sqlx::postgres::PgPoolOptions;
+use std::io::IsTerminal;
+use std::net::SocketAddr;
+use std::time::Duration;
+use tower_http::trace::TraceLayer;
+use tracing_subscriber::EnvFilter;
 
 // --- DAFTAR MODUL ---
+mod account;
+mod account_token;
 mod admin;
+mod api_key;
 mod app_state;
+mod audit;
+mod auth;
+mod client_info;
+mod config;
+mod content;
+mod cors;
+mod data_export;
+mod error;
+mod headers;
+mod login_guard;
+mod mailer;
+mod password;
+mod me;
+mod rate_limit;
+mod request_id;
+mod validation;
 mod kuliner;
 mod tempat_nongkrong;
 mod user;
@@ -15,98 +39,219 @@ mod wisata_alam;
 mod wisata_pendidikan;
 mod chatbot; // Modul Chatbot
 mod news;    // Modul Berita
+mod feed;    // RSS & Atom feed berita
+mod markdown;
+mod slug;
+mod etag;
+mod trash;
+mod two_factor;
+mod revision;
+mod session;
+mod sitemap;
 
 use crate::app_state::AppState;
+use crate::rate_limit::RateLimiter;
+use crate::auth::JwtKeys;
+use crate::config::Config;
+use crate::error::AppError;
 
 // ADMIN + USER HANDLERS
-use crate::admin::{admin_login_handler, admin_register_handler};
-use crate::user::{login_user, register_user};
+use crate::account::login;
+use crate::admin::admin_register_handler;
+use crate::user::{forgot_password, register_user, resend_verification, reset_password, verify_email};
+
+// PROFIL USER HANDLERS
+use crate::me::{change_password, delete_me, get_me, update_me};
+use crate::data_export::{download_export, get_export_status, request_export};
+use crate::session::{list_sessions, logout, refresh, revoke_other_sessions, revoke_session};
 
 // WISATA ALAM HANDLERS
 use crate::wisata_alam::{
-    create_wisata, delete_wisata_alam, get_wisata_alam, get_wisata_alam_by_id, update_wisata_alam,
+    create_wisata, delete_wisata_alam, get_wisata_alam, get_wisata_alam_by_id, get_wisata_alam_by_slug,
+    patch_wisata_alam, update_wisata_alam,
 };
 
 // WISATA PENDIDIKAN HANDLERS
 use crate::wisata_pendidikan::{
-    create_wisata_pendidikan, get_wisata_pendidikan, get_wisata_pendidikan_by_id,
+    create_wisata_pendidikan, delete_wisata_pendidikan, get_wisata_pendidikan, get_wisata_pendidikan_by_id,
+    get_wisata_pendidikan_by_slug, patch_wisata_pendidikan, update_wisata_pendidikan,
 };
 
 // KULINER HANDLERS
 use crate::kuliner::{
-    create_kuliner, delete_kuliner, get_kuliner, get_kuliner_id, update_kuliner,
+    create_kuliner, delete_kuliner, get_kuliner, get_kuliner_by_slug, get_kuliner_id, patch_kuliner, update_kuliner,
 };
 
 // TEMPAT NONGKRONG HANDLERS
 use crate::tempat_nongkrong::{
-    create_tempat_nongkrong, delete_tempat_nongkrong, get_tempat_nongkrong, get_tempat_nongkrong_id,
-    update_tempat_nongkrong,
+    create_tempat_nongkrong, delete_tempat_nongkrong, get_tempat_nongkrong, get_tempat_nongkrong_by_slug,
+    get_tempat_nongkrong_id, patch_tempat_nongkrong, update_tempat_nongkrong,
 };
 
 // CHATBOT HANDLERS
 use crate::chatbot::{save_chat_log, get_chat_stats};
 
 // NEWS HANDLERS
-use crate::news::{get_all_news, add_news, delete_news};
+use crate::news::{get_all_news, add_news, delete_news, get_news_by_slug};
+use crate::feed::{news_atom, news_rss};
+use crate::sitemap::{sitemap_index, sitemap_page, SitemapCache};
+
+// TRASH HANDLERS
+use crate::trash::{list_trash, purge_from_trash, restore_from_trash};
+
+// REVISION HANDLERS
+use crate::revision::{get_history, rollback};
+
+// 2FA ADMIN HANDLERS
+use crate::two_factor::{disable, enable, get_status, regenerate_recovery_codes, reset_for_admin, setup, verify_login};
+
+// AUDIT LOG HANDLERS
+use crate::audit::{export_audit_csv, list_audit};
+
+// API KEY HANDLERS
+use crate::api_key::{create_api_key, get_api_key_usage, list_api_keys, revoke_api_key};
 
 #[tokio::main]
 async fn main() {
     // Load .env file
     dotenvy::dotenv().ok();
 
-    // Koneksi Database
-    let db_url = std::env::var("DATABASE_URL").expect("DATABASE_URL is not set in .env file");
+    // Config dari config.toml (opsional) + env, semua kesalahan ditampilkan sekaligus lalu server berhenti
+    let config = Config::load().unwrap_or_else(|e| {
+        eprintln!("{e}");
+        std::process::exit(1);
+    });
+
+    // Warna hanya di terminal, log Docker / Spaces tetap polos
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::new(&config.server.log_level))
+        .with_ansi(std::io::stdout().is_terminal())
+        .init();
 
-    let pool = sqlx::postgres::PgPool::connect(&db_url)
+    // Koneksi Database
+    let pool = PgPoolOptions::new()
+        .max_connections(config.database.max_connections)
+        .min_connections(config.database.min_connections)
+        .acquire_timeout(Duration::from_secs(config.database.acquire_timeout_secs))
+        .connect(&config.database.url)
         .await
-        .expect("Failed to create postgre database pool");
+        .unwrap_or_else(|e| {
+            tracing::error!("Failed to connect to the database (database.url / DATABASE_URL): {e}");
+            std::process::exit(1);
+        });
+
+    // Berita lama (teks polos) dirender ke HTML sekali saja
+    match news::backfill_content_html(&pool).await {
+        Ok(0) => {}
+        Ok(n) => tracing::info!("Rendered content_html for {} news rows", n),
+        Err(e) => tracing::error!("Failed to backfill news content_html: {:?}", e),
+    }
+
+    // Data yang dihapus admin disimpan sekian hari di tempat sampah sebelum dihapus permanen
+    trash::spawn_purge_task(pool.clone(), config.content.trash_retention_days);
+    // File export data pribadi yang kedaluwarsa dibersihkan tiap jam
+    data_export::spawn_cleanup_task(pool.clone());
+    // Sesi login yang sudah lama berakhir dihapus tiap jam
+    session::spawn_cleanup_task(pool.clone());
+    // Bucket rate limit yang sudah penuh lagi dibuang tiap 10 menit
+    let rate_limiter = RateLimiter::default();
+    rate_limit::spawn_cleanup_task(rate_limiter.clone());
 
-    let state = AppState { pool };
+    let mailer = mailer::from_config(&config.mail).unwrap_or_else(|e| {
+        tracing::error!("Failed to configure mailer: {e}");
+        std::process::exit(1);
+    });
+    // Sudah divalidasi di Config::load
+    let cors = config.cors.layer().expect("CORS config is validated at startup");
+    let trusted_proxies = config.trusted_proxies().expect("trusted proxies are validated at startup");
 
-    // Konfigurasi CORS (Izinkan semua origin agar Vercel bisa akses)
-    let cors = CorsLayer::new()
-        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
-        .allow_origin(Any)
-        .allow_headers(Any);
+    let state = AppState {
+        pool,
+        frontend_url: config.server.frontend_url.clone(),
+        api_url: config.server.public_api_url.clone(),
+        sitemap: SitemapCache::new(config.content.sitemap_max_urls),
+        trash_retention_days: config.content.trash_retention_days,
+        jwt: JwtKeys::new(&config.auth.jwt_secret, config.auth.jwt_ttl_minutes),
+        admin_2fa_required: config.auth.admin_2fa_required,
+        mailer,
+        password_policy: config.password.clone(),
+        rate_limiter,
+        rate_limit: config.rate_limit.clone(),
+        trusted_proxies,
+    };
 
     let app = Router::new()
         // ===== AUTH USER =====
         .route("/register", post(register_user))
-        .route("/login", post(login_user))
+        .route("/login", post(login))
+        .route("/forgot_password", post(forgot_password))
+        .route("/reset_password", post(reset_password))
+        .route("/verify_email", post(verify_email))
+        .route("/resend_verification", post(resend_verification))
+
+        // ===== SESI LOGIN (token user maupun admin) =====
+        .route("/refresh", post(refresh))
+        .route("/logout", post(logout))
+        .route("/api/sessions", get(list_sessions).delete(revoke_other_sessions))
+        .route("/api/sessions/{id}", delete(revoke_session))
+
+        // ===== PROFIL USER (token dari /login) =====
+        .route("/api/me", get(get_me).patch(update_me).delete(delete_me))
+        .route("/api/me/password", post(change_password))
+        .route("/api/me/export", post(request_export))
+        .route("/api/me/export/{id}", get(get_export_status))
+        .route("/api/me/export/{id}/download", get(download_export))
         
         // ===== AUTH ADMIN =====
         .route("/admin_register", post(admin_register_handler))
-        .route("/admin_login", post(admin_login_handler))
+        // Alias lama, sama persis dengan /login
+        .route("/admin_login", post(login))
+        .route("/admin_login/verify", post(verify_login))
+
+        // ===== 2FA ADMIN =====
+        .route("/api/admin/2fa", get(get_status))
+        .route("/api/admin/2fa/setup", post(setup))
+        .route("/api/admin/2fa/enable", post(enable))
+        .route("/api/admin/2fa/recovery_codes", post(regenerate_recovery_codes))
+        .route("/api/admin/2fa/disable", post(disable))
+        .route("/api/admin/{id}/2fa/reset", post(reset_for_admin))
         
         // ===== WISATA ALAM =====
         .route("/wisata_alam", get(get_wisata_alam))
-        .route("/wisata_alam/:id", get(get_wisata_alam_by_id)) // Perbaikan: pakai :id
+        .route("/wisata_alam/{id}", get(get_wisata_alam_by_id))
+        .route("/wisata_alam/slug/{slug}", get(get_wisata_alam_by_slug))
         .route("/api/add_wisata", post(create_wisata))
         .route("/add_wisata", post(create_wisata)) 
-        .route("/api/update_wisata/:id", put(update_wisata_alam)) // Perbaikan: pakai :id
-        .route("/api/delete_wisata/:id", delete(delete_wisata_alam)) // Perbaikan: pakai :id
+        .route("/api/update_wisata/{id}", put(update_wisata_alam).patch(patch_wisata_alam))
+        .route("/api/delete_wisata/{id}", delete(delete_wisata_alam))
 
         // ===== WISATA PENDIDIKAN =====
         .route("/wisata_pendidikan", get(get_wisata_pendidikan))
-        .route("/wisata_pendidikan/:id", get(get_wisata_pendidikan_by_id))
+        .route("/wisata_pendidikan/{id}", get(get_wisata_pendidikan_by_id))
+        .route("/wisata_pendidikan/slug/{slug}", get(get_wisata_pendidikan_by_slug))
+        .route("/api/add_wisata_pendidikan", post(create_wisata_pendidikan))
         .route("/add_wisata_pendidikan", post(create_wisata_pendidikan))
+        .route("/api/update_wisata_pendidikan/{id}", put(update_wisata_pendidikan).patch(patch_wisata_pendidikan))
+        .route("/api/delete_wisata_pendidikan/{id}", delete(delete_wisata_pendidikan))
 
         // ===== KULINER =====
         .route("/kuliner", get(get_kuliner))
-        .route("/kuliner/:id", get(get_kuliner_id))
+        .route("/kuliner/{id}", get(get_kuliner_id))
+        .route("/kuliner/slug/{slug}", get(get_kuliner_by_slug))
         .route("/get_kuliner", get(get_kuliner))
         .route("/api/add_kuliner", post(create_kuliner))
         .route("/add_kuliner", post(create_kuliner)) 
-        .route("/api/update_kuliner/:id", put(update_kuliner))
-        .route("/api/delete_kuliner/:id", delete(delete_kuliner))
+        .route("/api/update_kuliner/{id}", put(update_kuliner).patch(patch_kuliner))
+        .route("/api/delete_kuliner/{id}", delete(delete_kuliner))
 
         // ===== TEMPAT NONGKRONG (CAFE) =====
         .route("/tempat_nongkrong", get(get_tempat_nongkrong))
-        .route("/tempat_nongkrong/:id", get(get_tempat_nongkrong_id))
+        .route("/tempat_nongkrong/{id}", get(get_tempat_nongkrong_id))
+        .route("/tempat_nongkrong/slug/{slug}", get(get_tempat_nongkrong_by_slug))
         .route("/api/add_tempat_nongkrong", post(create_tempat_nongkrong))
         .route("/add_tempat_nongkrong", post(create_tempat_nongkrong))
-        .route("/api/update_cafe/:id", put(update_tempat_nongkrong))
-        .route("/api/delete_cafe/:id", delete(delete_tempat_nongkrong))
+        .route("/api/update_cafe/{id}", put(update_tempat_nongkrong).patch(patch_tempat_nongkrong))
+        .route("/api/delete_cafe/{id}", delete(delete_tempat_nongkrong))
 
         // ===== CHATBOT =====
         .route("/api/chat/log", post(save_chat_log))   
@@ -114,19 +259,55 @@ async fn main() {
 
         // ===== NEWS / BERITA =====
         .route("/api/news", get(get_all_news).post(add_news))
-        .route("/api/news/:id", delete(delete_news))
+        .route("/api/news/{id}", delete(delete_news))
+        .route("/api/news/slug/{slug}", get(get_news_by_slug))
+        .route("/api/news/rss.xml", get(news_rss))
+        .route("/api/news/atom.xml", get(news_atom))
+
+        // ===== TEMPAT SAMPAH (ADMIN) =====
+        .route("/api/trash", get(list_trash))
+        .route("/api/trash/{table}/{id}", delete(purge_from_trash))
+        .route("/api/trash/{table}/{id}/restore", post(restore_from_trash))
+
+        // ===== RIWAYAT REVISI (ADMIN) =====
+        .route("/api/revisions/{table}/{id}", get(get_history))
+        .route("/api/revisions/{table}/{id}/rollback", post(rollback))
 
+        // ===== AUDIT LOG KEAMANAN (ADMIN) =====
+        .route("/api/audit", get(list_audit))
+        .route("/api/audit/export.csv", get(export_audit_csv))
+
+        // ===== API KEY PARTNER (ADMIN) =====
+        .route("/api/api_keys", get(list_api_keys).post(create_api_key))
+        .route("/api/api_keys/{id}", delete(revoke_api_key))
+        .route("/api/api_keys/{id}/usage", get(get_api_key_usage))
+
+        // ===== SEO =====
+        .route("/sitemap.xml", get(sitemap_index))
+        .route("/sitemaps/{file}", get(sitemap_page))
+
+        .fallback(|| async { AppError::not_found("Route tidak ditemukan") })
+        // Rate limit per kelompok route (auth / read / write) atau per API key
+        .layer(middleware::from_fn_with_state(state.clone(), rate_limit::rate_limit_middleware))
+        // Request dengan header X-API-Key dicek di sini (limit per IP, lalu scope, read-only), sebelum rate limit
+        .layer(middleware::from_fn_with_state(state.clone(), api_key::api_key_middleware))
         .with_state(state)
+        .layer(middleware::from_fn(request_id::request_id_middleware))
+        // Log tiap request (level debug, aktif dengan LOG_LEVEL=tower_http=debug)
+        .layer(TraceLayer::new_for_http())
         .layer(cors);
 
-    // --- KONFIGURASI PORT HUGGING FACE ---
-    // Hugging Face Spaces Docker WAJIB menggunakan Port 7860
-    let port = 7860;
-    println!("🚀 Server is running on port {}", port);
-
-    let listener = tokio::net::TcpListener::bind(format!("0.0.0.0:{}", port))
-        .await
-        .unwrap();
+    // Default 0.0.0.0:7860 (port wajib Hugging Face Spaces), ganti lewat SERVER_HOST / PORT untuk lokal atau Docker lain
+    let address = config.bind_address();
+    let listener = tokio::net::TcpListener::bind(address).await.unwrap_or_else(|e| {
+        tracing::error!("Failed to bind {address} (server.host / server.port): {e}");
+        std::process::exit(1);
+    });
+    tracing::info!("🚀 Server is running on {}", address);
 
-    axum::serve(listener, app).await.unwrap();
+    // ConnectInfo = IP client, atau proxy yang meneruskan X-Forwarded-For (server.trusted_proxies)
+    if let Err(e) = axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await {
+        tracing::error!("Server stopped: {e}");
+        std::process::exit(1);
+    }
 }
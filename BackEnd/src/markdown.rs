@@ -0,0 +1,121 @@
+use ammonia::Builder;
+use pulldown_cmark::{html, Event, Options, Parser, TagEnd};
+use std::collections::HashSet;
+
+// Tag yang boleh muncul di HTML berita (heading, list, link, gambar inline, dll)
+const ALLOWED_TAGS: &[&str] = &[
+    "h1", "h2", "h3", "h4", "h5", "h6", "p", "br", "hr", "strong", "em", "del", "blockquote",
+    "ul", "ol", "li", "a", "img", "code", "pre", "table", "thead", "tbody", "tr", "th", "td",
+];
+
+fn options() -> Options {
+    Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH
+}
+
+/// Render Markdown ke HTML yang sudah disanitasi (tanpa script, event handler, atau URL berbahaya).
+pub fn render_html(source: &str) -> String {
+    let mut raw = String::new();
+    html::push_html(&mut raw, Parser::new_ext(source, options()));
+
+    Builder::default()
+        .tags(ALLOWED_TAGS.iter().copied().collect::<HashSet<_>>())
+        .tag_attributes(
+            [("a", ["href", "title"].into()), ("img", ["src", "alt", "title"].into())].into(),
+        )
+        .generic_attributes(HashSet::new())
+        .url_schemes(["http", "https", "mailto"].into())
+        .link_rel(Some("noopener noreferrer nofollow"))
+        .clean(&raw)
+        .to_string()
+}
+
+/// Ambil teks polos dari Markdown, dipakai untuk ringkasan & hitung waktu baca.
+pub fn to_plain_text(source: &str) -> String {
+    let mut text = String::new();
+    for event in Parser::new_ext(source, options()) {
+        match event {
+            Event::Text(t) | Event::Code(t) => text.push_str(&t),
+            Event::SoftBreak | Event::HardBreak | Event::End(TagEnd::Paragraph | TagEnd::Heading(_) | TagEnd::Item) => {
+                text.push(' ')
+            }
+            _ => {}
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_basic_markdown() {
+        let html = render_html("# Judul\n\n**tebal** dan *miring*\n\n- satu\n- dua");
+        assert!(html.contains("<h1>Judul</h1>"));
+        assert!(html.contains("<strong>tebal</strong>"));
+        assert!(html.contains("<em>miring</em>"));
+        assert!(html.contains("<li>satu</li>"));
+    }
+
+    #[test]
+    fn strips_script_tags() {
+        let html = render_html("halo\n\n<script>alert(1)</script>\n\ninline <script>alert(2)</script> teks");
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("alert("));
+        assert!(html.contains("halo"));
+    }
+
+    #[test]
+    fn strips_event_handlers() {
+        let html = render_html("<img src=\"https://example.com/a.png\" onerror=\"alert(1)\">\n\n<p onclick=\"x()\">p</p>");
+        assert!(!html.contains("onerror"));
+        assert!(!html.contains("onclick"));
+        assert!(html.contains("src=\"https://example.com/a.png\""));
+    }
+
+    #[test]
+    fn drops_unsafe_link_schemes() {
+        for source in [
+            "[klik](javascript:alert(1))",
+            "[klik](JaVaScRiPt:alert(1))",
+            "[klik](data:text/html;base64,PHNjcmlwdD4=)",
+            "<a href=\"javascript:alert(1)\">klik</a>",
+            "![gambar](data:image/svg+xml;base64,PHN2Zz4=)",
+        ] {
+            let html = render_html(source).to_lowercase();
+            assert!(!html.contains("javascript:"), "{source} -> {html}");
+            assert!(!html.contains("data:"), "{source} -> {html}");
+        }
+    }
+
+    #[test]
+    fn keeps_safe_links_with_rel() {
+        let html = render_html("[situs](https://example.com) [surat](mailto:a@example.com)");
+        assert!(html.contains("href=\"https://example.com\""));
+        assert!(html.contains("href=\"mailto:a@example.com\""));
+        assert!(html.contains("rel=\"noopener noreferrer nofollow\""));
+    }
+
+    #[test]
+    fn removes_disallowed_raw_html() {
+        let html = render_html(
+            "<iframe src=\"https://evil.example\"></iframe>\n\n<div style=\"x\"><b>tebal</b></div>\n\n<form><input></form>",
+        );
+        assert!(!html.contains("<iframe"));
+        assert!(!html.contains("<div"));
+        assert!(!html.contains("style="));
+        assert!(!html.contains("<form"));
+        assert!(!html.contains("<input"));
+    }
+
+    #[test]
+    fn plain_text_has_no_markup_or_raw_html() {
+        let text = to_plain_text("# Judul\n\nIsi **tebal** `kode`\n\n<script>alert(1)</script>\n\n- a\n- b");
+        assert!(text.contains("Judul"));
+        assert!(text.contains("tebal"));
+        assert!(text.contains("kode"));
+        assert!(!text.contains('<'));
+        assert!(!text.contains("alert"));
+        assert!(!text.contains("**"));
+    }
+}
@@ -0,0 +1,207 @@
+use axum::{extract::State, http::StatusCode, Json};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::{FromRow, PgPool};
+use validator::{Validate, ValidationError};
+
+use crate::account::{self, Account};
+use crate::account_token::{self, Purpose};
+use crate::app_state::AppState;
+use crate::audit::{self, AuditEvent};
+use crate::auth::{AuthUser, USER_ROLE};
+use crate::client_info::ClientInfo;
+use crate::error::{AppError, AppResult};
+use crate::login_guard::{self, LOGIN_SCOPE};
+use crate::password;
+use crate::session;
+use crate::validation::{validate_image_url, ValidJson};
+
+const PROFILE_COLUMNS: &str = "id, username, email, email_verified_at, display_name, avatar_url, home_city";
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct Profile {
+    pub id: i32,
+    pub username: String,
+    pub email: String,
+    pub email_verified_at: Option<DateTime<Utc>>,
+    pub display_name: Option<String>,
+    pub avatar_url: Option<String>,
+    pub home_city: Option<String>,
+}
+
+// Field yang tidak dikirim tetap, string kosong menghapus isinya
+#[derive(Debug, Deserialize, Validate)]
+pub struct ProfilePatch {
+    #[validate(length(max = 50, message = "display_name maksimal 50 karakter"))]
+    pub display_name: Option<String>,
+    #[validate(length(max = 500, message = "maksimal 500 karakter"), custom(function = "validate_avatar_url"))]
+    pub avatar_url: Option<String>,
+    #[validate(length(max = 100, message = "home_city maksimal 100 karakter"))]
+    pub home_city: Option<String>,
+}
+
+// new_password dicek dengan state.password_policy
+#[derive(Deserialize, Validate)]
+pub struct ChangePasswordRequest {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct DeleteAccountRequest {
+    pub password: String,
+}
+
+fn validate_avatar_url(value: &str) -> Result<(), ValidationError> {
+    if value.trim().is_empty() { Ok(()) } else { validate_image_url(value) }
+}
+
+async fn load_user(state: &AppState, user: &AuthUser) -> AppResult<Account> {
+    account::find_by_id(&state.pool, user.id)
+        .await?
+        .ok_or_else(|| AppError::Unauthorized("Akun tidak ditemukan, silakan login ulang".to_string()))
+}
+
+// Ganti password / hapus akun butuh password sekarang. Tebakan salah dibatasi sama seperti login
+async fn confirm_password(
+    state: &AppState,
+    client: &ClientInfo,
+    user: &Account,
+    password: &str,
+    event: &str,
+) -> AppResult<()> {
+    let ip = client.ip.as_deref();
+    login_guard::begin_attempt(&state.pool, LOGIN_SCOPE, &user.username, ip).await?;
+
+    if !password::verify(password, Some(&user.password)).valid {
+        let failure = AuditEvent::failure(event)
+            .actor(Some(user.id), &user.username)
+            .details(json!({ "reason": "wrong_password" }));
+        audit::record(&state.pool, client, failure).await?;
+        return Err(AppError::Forbidden("Password sekarang salah".to_string()));
+    }
+    login_guard::attempt_succeeded(&state.pool, LOGIN_SCOPE, &user.username, ip).await?;
+    Ok(())
+}
+
+pub async fn load_profile(pool: &PgPool, user_id: i32) -> Result<Option<Profile>, sqlx::Error> {
+    sqlx::query_as::<_, Profile>(&format!("SELECT {PROFILE_COLUMNS} FROM accounts WHERE id = $1"))
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+}
+
+// GET /api/me
+pub async fn get_me(State(state): State<AppState>, user: AuthUser) -> AppResult<Json<Profile>> {
+    let profile = load_profile(&state.pool, user.id)
+        .await?
+        .ok_or_else(|| AppError::Unauthorized("Akun tidak ditemukan, silakan login ulang".to_string()))?;
+
+    Ok(Json(profile))
+}
+
+// PATCH /api/me { "display_name": "...", "avatar_url": "...", "home_city": "..." }
+pub async fn update_me(
+    State(state): State<AppState>,
+    user: AuthUser,
+    ValidJson(payload): ValidJson<ProfilePatch>,
+) -> AppResult<Json<Profile>> {
+    let trim = |value: &Option<String>| value.as_deref().map(str::trim).map(str::to_string);
+
+    let profile = sqlx::query_as::<_, Profile>(&format!(
+        "UPDATE accounts SET
+            display_name = CASE WHEN $1::text IS NULL THEN display_name ELSE NULLIF($1, '') END,
+            avatar_url   = CASE WHEN $2::text IS NULL THEN avatar_url   ELSE NULLIF($2, '') END,
+            home_city    = CASE WHEN $3::text IS NULL THEN home_city    ELSE NULLIF($3, '') END
+         WHERE id = $4
+         RETURNING {PROFILE_COLUMNS}"
+    ))
+    .bind(trim(&payload.display_name))
+    .bind(trim(&payload.avatar_url))
+    .bind(trim(&payload.home_city))
+    .bind(user.id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::Unauthorized("Akun tidak ditemukan, silakan login ulang".to_string()))?;
+
+    Ok(Json(profile))
+}
+
+// POST /api/me/password { "current_password": "...", "new_password": "..." }
+pub async fn change_password(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    client: ClientInfo,
+    ValidJson(payload): ValidJson<ChangePasswordRequest>,
+) -> AppResult<StatusCode> {
+    state.password_policy.check("new_password", &payload.new_password)?;
+    let user = load_user(&state, &auth).await?;
+    confirm_password(&state, &client, &user, &payload.current_password, "user.password_changed").await?;
+
+    let hashed = password::hash(&payload.new_password)?;
+    sqlx::query("UPDATE accounts SET password = $1 WHERE id = $2")
+        .bind(&hashed)
+        .bind(user.id)
+        .execute(&state.pool)
+        .await?;
+
+    // Link reset password yang masih beredar tidak berlaku lagi, perangkat lain harus login ulang
+    account_token::revoke(&state.pool, user.id, Purpose::PasswordReset).await?;
+    session::revoke_all(&state.pool, USER_ROLE, user.id, Some(&auth.session_id), "password_changed").await?;
+    login_guard::record_success(&state.pool, LOGIN_SCOPE, &user.username).await?;
+
+    let event = AuditEvent::success("user.password_changed").actor(Some(user.id), &user.username);
+    audit::record(&state.pool, &client, event).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// DELETE /api/me { "password": "..." }
+// Akun dihapus permanen. Data milik akun ikut terhapus lewat foreign key ON DELETE CASCADE
+// (riwayat chat, token email). Tabel baru yang menyimpan data user (ulasan, favorit, ...) harus ikut aturan ini.
+// Baris audit_log akun ini tetap ada (urutan kejadian tetap utuh), tapi username diganti "deleted:<id>" dan
+// IP / user agent / email dihapus. Hapus akun, cabut sesi dan audit satu transaksi.
+pub async fn delete_me(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    client: ClientInfo,
+    ValidJson(payload): ValidJson<DeleteAccountRequest>,
+) -> AppResult<StatusCode> {
+    let user = load_user(&state, &auth).await?;
+    confirm_password(&state, &client, &user, &payload.password, "user.deleted").await?;
+
+    let mut tx = state.pool.begin().await?;
+    let chat_logs: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM chat_logs WHERE user_id = $1")
+        .bind(user.id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    sqlx::query("DELETE FROM accounts WHERE id = $1")
+        .bind(user.id)
+        .execute(&mut *tx)
+        .await?;
+    session::revoke_all(&mut *tx, USER_ROLE, user.id, None, "account_deleted").await?;
+
+    // Termasuk login gagal / ditahan yang dicatat tanpa actor_id, hanya dengan username yang dicoba
+    let pseudonym = format!("deleted:{}", user.id);
+    sqlx::query(
+        "UPDATE audit_log SET username = $3, ip = NULL, user_agent = NULL, details = details - 'email'
+         WHERE actor_id = $1 OR (actor_id IS NULL AND lower(username) = lower($2))",
+    )
+    .bind(user.id)
+    .bind(&user.username)
+    .bind(&pseudonym)
+    .execute(&mut *tx)
+    .await?;
+
+    let event = AuditEvent::success("user.deleted")
+        .actor(Some(user.id), &pseudonym)
+        .details(json!({ "chat_logs_deleted": chat_logs }));
+    audit::record(&mut *tx, &ClientInfo::default(), event).await?;
+    tx.commit().await?;
+
+    login_guard::record_success(&state.pool, LOGIN_SCOPE, &user.username).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -1,86 +1,249 @@
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Redirect, Response},
     Json,
 };
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use validator::Validate;
 use crate::app_state::AppState;
+use crate::auth::AdminUser;
+use crate::error::{AppError, AppPath, AppQuery, AppResult};
+use crate::validation::{validate_date_range, validate_image_url, validate_not_blank, ValidJson};
+use crate::markdown;
+use crate::revision::{self, Action};
+use crate::slug;
+use crate::headers;
+
+const TABLE: &str = "news";
+
+// Kecepatan baca rata-rata (kata per menit) untuk hitung read_minutes
+const WORDS_PER_MINUTE: usize = 200;
+const DEFAULT_PER_PAGE: i64 = 20;
+const MAX_PER_PAGE: i64 = 100;
 
 // Model Database
 #[derive(Debug, Serialize, FromRow)]
 pub struct NewsItem {
     pub id: i32,
+    pub slug: String,
     pub title: String,
     pub category: String,
     pub image_url: String,
+    // Sumber Markdown + HTML hasil render yang sudah disanitasi
     pub content: String,
-    pub date: String,
+    // NULL di database (belum dirender saat startup) dibaca sebagai ''
+    pub content_html: String,
+    pub published_at: DateTime<Utc>,
     pub read_minutes: i32,
 }
 
 // Model Input dari Admin
-#[derive(Debug, Deserialize)]
+// `content` berupa Markdown. `date` (YYYY-MM-DD) masih diterima untuk form lama, read_minutes dihitung server.
+#[derive(Debug, Deserialize, Validate)]
 pub struct NewsPayload {
+    #[validate(length(min = 1, max = 200, message = "title harus 1-200 karakter"), custom(function = "validate_not_blank"))]
     pub title: String,
+    #[validate(length(min = 1, max = 50, message = "category harus 1-50 karakter"), custom(function = "validate_not_blank"))]
     pub category: String,
+    #[validate(length(max = 500, message = "maksimal 500 karakter"), custom(function = "validate_image_url"))]
     pub image_url: String,
+    #[validate(length(min = 1, max = 100_000, message = "content harus 1-100000 karakter"), custom(function = "validate_not_blank"))]
     pub content: String,
-    pub date: String,
-    pub read_minutes: i32,
+    #[serde(default)]
+    pub published_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub date: Option<NaiveDate>,
+}
+
+// Filter & pagination untuk GET /api/news
+#[derive(Debug, Deserialize)]
+pub struct NewsQuery {
+    pub category: Option<String>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+impl NewsPayload {
+    fn published_at(&self) -> DateTime<Utc> {
+        self.published_at
+            .or_else(|| self.date.and_then(|d| d.and_hms_opt(0, 0, 0)).map(|dt| dt.and_utc()))
+            .unwrap_or_else(Utc::now)
+    }
+}
+
+/// Estimasi waktu baca dari jumlah kata (teks polos hasil Markdown), minimal 1 menit.
+pub fn read_minutes(content: &str) -> i32 {
+    let words = markdown::to_plain_text(content).split_whitespace().count();
+    words.div_ceil(WORDS_PER_MINUTE).max(1) as i32
 }
 
 // 1. GET ALL NEWS (Untuk Halaman Home & Admin)
+// Total data dikirim lewat header X-Total-Count supaya body tetap berupa array.
 pub async fn get_all_news(
     State(state): State<AppState>,
-) -> Result<Json<Vec<NewsItem>>, (StatusCode, String)> {
-    let rows = sqlx::query_as::<_, NewsItem>("SELECT * FROM news ORDER BY id DESC")
-        .fetch_all(&state.pool)
-        .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("DB Error: {e}")))?;
+    AppQuery(query): AppQuery<NewsQuery>,
+) -> AppResult<(HeaderMap, Json<Vec<NewsItem>>)> {
+    validate_date_range(query.from, query.to)?;
+    let per_page = query.per_page.unwrap_or(DEFAULT_PER_PAGE).clamp(1, MAX_PER_PAGE);
+    let page = query.page.unwrap_or(1).max(1);
+
+    let total: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*) FROM news
+        WHERE deleted_at IS NULL
+          AND ($1::text IS NULL OR category = $1)
+          AND ($2::date IS NULL OR published_at >= $2)
+          AND ($3::date IS NULL OR published_at < $3 + 1)
+        "#,
+    )
+    .bind(&query.category)
+    .bind(query.from)
+    .bind(query.to)
+    .fetch_one(&state.pool)
+    .await?;
+
+    let rows = sqlx::query_as::<_, NewsItem>(
+        r#"
+        SELECT id, slug, title, category, image_url, content, COALESCE(content_html, '') AS content_html,
+               published_at, read_minutes
+        FROM news
+        WHERE deleted_at IS NULL
+          AND ($1::text IS NULL OR category = $1)
+          AND ($2::date IS NULL OR published_at >= $2)
+          AND ($3::date IS NULL OR published_at < $3 + 1)
+        ORDER BY published_at DESC, id DESC
+        LIMIT $4 OFFSET $5
+        "#,
+    )
+    .bind(&query.category)
+    .bind(query.from)
+    .bind(query.to)
+    .bind(per_page)
+    .bind((page - 1) * per_page)
+    .fetch_all(&state.pool)
+    .await?;
 
-    Ok(Json(rows))
+    Ok((headers::paging(total, page, per_page), Json(rows)))
 }
 
 // 2. ADD NEWS (Untuk Admin)
 pub async fn add_news(
     State(state): State<AppState>,
-    Json(payload): Json<NewsPayload>,
-) -> Result<(StatusCode, Json<NewsItem>), (StatusCode, String)> {
-    let inserted = sqlx::query_as::<_, NewsItem>(
+    admin: AdminUser,
+    ValidJson(payload): ValidJson<NewsPayload>,
+) -> AppResult<(StatusCode, Json<NewsItem>)> {
+    let published_at = payload.published_at();
+    let minutes = read_minutes(&payload.content);
+    let content_html = markdown::render_html(&payload.content);
+
+    // Slug, INSERT dan revisi dalam satu transaksi, diulang kalau slug keburu dipakai request lain
+    let mut attempt = 1;
+    let inserted = loop {
+        let mut tx = state.pool.begin().await?;
+        let result = async {
+            let new_slug = slug::unique_slug(&mut tx, TABLE, &payload.title, None).await?;
+
+            let inserted = sqlx::query_as::<_, NewsItem>(
+                r#"
+                INSERT INTO news (title, category, image_url, content, content_html, published_at, read_minutes, slug)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                RETURNING id, slug, title, category, image_url, content, content_html, published_at, read_minutes
+                "#
+            )
+            .bind(&payload.title)
+            .bind(&payload.category)
+            .bind(&payload.image_url)
+            .bind(&payload.content)
+            .bind(&content_html)
+            .bind(published_at)
+            .bind(minutes)
+            .bind(&new_slug)
+            .fetch_one(&mut *tx)
+            .await?;
+
+            revision::record(&mut tx, TABLE, inserted.id, Action::Create, &admin, None).await?;
+            Ok(inserted)
+        }
+        .await;
+        if let Some(inserted) = slug::finish(tx, result, &mut attempt).await? {
+            break inserted;
+        }
+    };
+
+    state.sitemap.invalidate();
+    Ok((StatusCode::CREATED, Json(inserted)))
+}
+
+// GET /api/news/slug/{slug} -> slug lama di-redirect ke slug terbaru
+pub async fn get_news_by_slug(
+    State(state): State<AppState>,
+    AppPath(slug_param): AppPath<String>,
+) -> AppResult<Response> {
+    let row = sqlx::query_as::<_, NewsItem>(
         r#"
-        INSERT INTO news (title, category, image_url, content, date, read_minutes)
-        VALUES ($1, $2, $3, $4, $5, $6)
-        RETURNING *
-        "#
+        SELECT id, slug, title, category, image_url, content, COALESCE(content_html, '') AS content_html,
+               published_at, read_minutes
+        FROM news WHERE slug = $1 AND deleted_at IS NULL
+        "#,
     )
-    .bind(payload.title)
-    .bind(payload.category)
-    .bind(payload.image_url)
-    .bind(payload.content)
-    .bind(payload.date)
-    .bind(payload.read_minutes)
-    .fetch_one(&state.pool)
-    .await
-    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("DB Error: {e}")))?;
+    .bind(&slug_param)
+    .fetch_optional(&state.pool)
+    .await?;
 
-    Ok((StatusCode::CREATED, Json(inserted)))
+    if let Some(row) = row {
+        return Ok(Json(row).into_response());
+    }
+
+    match slug::find_redirect(&state.pool, TABLE, &slug_param).await? {
+        Some(current) => Ok(Redirect::permanent(&format!("/api/news/slug/{current}")).into_response()),
+        None => Err(AppError::not_found("Slug tidak ditemukan")),
+    }
 }
 
 // 3. DELETE NEWS (Untuk Admin)
 pub async fn delete_news(
     State(state): State<AppState>,
-    Path(id): Path<i32>,
-) -> Result<StatusCode, (StatusCode, String)> {
-    let result = sqlx::query("DELETE FROM news WHERE id = $1")
+    admin: AdminUser,
+    AppPath(id): AppPath<i32>,
+) -> AppResult<StatusCode> {
+    // Snapshot, soft delete dan revisi satu transaksi
+    let mut tx = state.pool.begin().await?;
+    let before = revision::snapshot(&mut tx, TABLE, id).await?;
+    let result = sqlx::query("UPDATE news SET deleted_at = now() WHERE id = $1 AND deleted_at IS NULL")
         .bind(id)
-        .execute(&state.pool)
-        .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("DB Error: {e}")))?;
+        .execute(&mut *tx)
+        .await?;
 
     if result.rows_affected() == 0 {
-        return Err((StatusCode::NOT_FOUND, "News ID not found".to_string()));
+        return Err(AppError::not_found("ID tidak ditemukan"));
     }
 
-    Ok(StatusCode::OK)
-}
\ No newline at end of file
+    revision::record(&mut tx, TABLE, id, Action::Delete, &admin, before).await?;
+    tx.commit().await?;
+    state.sitemap.invalidate();
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// Render berita lama yang belum punya content_html (NULL, lihat migrations/0025), dipanggil saat startup
+pub async fn backfill_content_html(pool: &sqlx::PgPool) -> Result<u64, sqlx::Error> {
+    let rows: Vec<(i32, String)> =
+        sqlx::query_as("SELECT id, content FROM news WHERE content_html IS NULL")
+            .fetch_all(pool)
+            .await?;
+
+    for (id, content) in &rows {
+        sqlx::query("UPDATE news SET content_html = $1, read_minutes = $2 WHERE id = $3")
+            .bind(markdown::render_html(content))
+            .bind(read_minutes(content))
+            .bind(id)
+            .execute(pool)
+            .await?;
+    }
+
+    Ok(rows.len() as u64)
+}
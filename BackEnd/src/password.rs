@@ -0,0 +1,141 @@
+use argon2::{
+    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use password_hash::rand_core::OsRng;
+use serde::Deserialize;
+use sqlx::PgPool;
+use std::collections::HashSet;
+use std::sync::LazyLock;
+use validator::{ValidationError, ValidationErrors};
+
+use crate::error::AppError;
+
+pub const DEFAULT_MIN_LENGTH: usize = 8;
+pub const DEFAULT_MAX_LENGTH: usize = 128;
+// Batas bawah konfigurasi: min di bawah 8 terlalu lemah, max di bawah 64 menghalangi passphrase / password manager
+const MIN_LENGTH_FLOOR: usize = 8;
+const MAX_LENGTH_FLOOR: usize = 64;
+
+// Daftar password umum / bocor, ikut dikompilasi ke binary (tidak perlu file saat jalan)
+static COMMON_PASSWORDS: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
+    include_str!("../data/common_passwords.txt")
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect()
+});
+
+// Hash dummy supaya username yang tidak ada butuh waktu verify yang sama (tidak bocor lewat timing)
+static DUMMY_HASH: LazyLock<String> =
+    LazyLock::new(|| hash("dummy-password-for-timing").expect("argon2 hash dummy"));
+
+/// Aturan password untuk daftar, reset dan ganti password. Bagian `[password]` di config, dicek saat server start.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub max_length: usize,
+    // Tolak password yang ada di data/common_passwords.txt
+    pub reject_common: bool,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self { min_length: DEFAULT_MIN_LENGTH, max_length: DEFAULT_MAX_LENGTH, reject_common: true }
+    }
+}
+
+impl PasswordPolicy {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.min_length < MIN_LENGTH_FLOOR {
+            return Err(format!("password.min_length (PASSWORD_MIN_LENGTH) must be at least {MIN_LENGTH_FLOOR}"));
+        }
+        if self.max_length < MAX_LENGTH_FLOOR {
+            return Err(format!("password.max_length (PASSWORD_MAX_LENGTH) must be at least {MAX_LENGTH_FLOOR}"));
+        }
+        if self.min_length > self.max_length {
+            return Err("password.min_length must not be greater than password.max_length".to_string());
+        }
+        Ok(())
+    }
+
+    /// Cek password baru. Error dikembalikan sebagai error validasi field (422), sama seperti validator lain.
+    pub fn check(&self, field: &'static str, password: &str) -> Result<(), AppError> {
+        let length = password.chars().count();
+
+        let error = if length < self.min_length {
+            Some(("length", format!("{field} minimal {} karakter", self.min_length)))
+        } else if length > self.max_length {
+            Some(("length", format!("{field} maksimal {} karakter", self.max_length)))
+        } else if self.reject_common && COMMON_PASSWORDS.contains(password.to_lowercase().as_str()) {
+            Some(("common_password", format!("{field} terlalu umum, pilih yang lain")))
+        } else {
+            None
+        };
+
+        match error {
+            Some((code, message)) => {
+                let mut errors = ValidationErrors::new();
+                errors.add(field, ValidationError::new(code).with_message(message.into()));
+                Err(AppError::Validation(errors))
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+/// Hash password baru dengan argon2id (salt acak, parameter default crate argon2).
+pub fn hash(password: &str) -> Result<String, AppError> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| AppError::Internal(format!("Failed to hash password: {e}")))
+}
+
+/// Hasil cek password.
+pub struct Verified {
+    pub valid: bool,
+    // Hash lama (bcrypt) -> perlu di-hash ulang dengan argon2 selagi password asli ada
+    pub needs_rehash: bool,
+}
+
+/// Verifikasi password terhadap hash argon2 atau bcrypt (akun lama).
+/// Kalau akun tidak ada tetap menjalankan argon2 terhadap hash dummy.
+pub fn verify(password: &str, stored_hash: Option<&str>) -> Verified {
+    let Some(stored) = stored_hash else {
+        let _ = verify_argon2(password, &DUMMY_HASH);
+        return Verified { valid: false, needs_rehash: false };
+    };
+
+    if stored.starts_with("$argon2") {
+        Verified { valid: verify_argon2(password, stored), needs_rehash: false }
+    } else {
+        let valid = bcrypt::verify(password, stored).unwrap_or(false);
+        Verified { valid, needs_rehash: valid }
+    }
+}
+
+fn verify_argon2(password: &str, stored: &str) -> bool {
+    PasswordHash::new(stored)
+        .map(|parsed| Argon2::default().verify_password(password.as_bytes(), &parsed).is_ok())
+        .unwrap_or(false)
+}
+
+/// Ganti hash bcrypt lama dengan argon2 setelah login berhasil. Gagal upgrade tidak menggagalkan login.
+pub async fn upgrade_hash(pool: &PgPool, account_id: i32, password: &str) {
+    let result = match hash(password) {
+        Ok(hashed) => sqlx::query("UPDATE accounts SET password = $1 WHERE id = $2")
+            .bind(hashed)
+            .bind(account_id)
+            .execute(pool)
+            .await
+            .map(|_| ())
+            .map_err(|e| format!("{e:?}")),
+        Err(e) => Err(format!("{e:?}")),
+    };
+    if let Err(e) = result {
+        tracing::warn!("Failed to upgrade password hash for account {}: {}", account_id, e);
+    }
+}
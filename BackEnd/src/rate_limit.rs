@@ -0,0 +1,394 @@
+use axum::{
+    extract::{Request, State},
+    http::{HeaderMap, HeaderValue, Method},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use crate::api_key::ApiKeyClient;
+use crate::app_state::AppState;
+use crate::auth;
+use crate::client_info::ClientInfo;
+use crate::error::AppError;
+use crate::headers::{RATELIMIT_LIMIT, RATELIMIT_POLICY, RATELIMIT_REMAINING, RATELIMIT_RESET};
+
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+// Endpoint login / daftar / token: dibatasi per IP, paling ketat
+const AUTH_PATHS: &[&str] = &[
+    "/register",
+    "/login",
+    "/admin_login",
+    "/admin_login/verify",
+    "/admin_register",
+    "/forgot_password",
+    "/reset_password",
+    "/verify_email",
+    "/resend_verification",
+    "/refresh",
+];
+
+/// Batas request: `capacity` request, bucket terisi penuh lagi dalam `period`.
+/// Di config ditulis sebagai string, mis. "60/m".
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Limit {
+    pub capacity: u32,
+    pub period: Duration,
+}
+
+impl Limit {
+    pub fn per_minute(capacity: u32) -> Self {
+        Self { capacity, period: Duration::from_secs(60) }
+    }
+
+    // Token yang bertambah per detik
+    fn rate(&self) -> f64 {
+        self.capacity as f64 / self.period.as_secs_f64()
+    }
+
+    /// "20/m", "300/1m", "1000/h", "5/30s" -> Limit. Satuan: s, m, h.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let invalid = || format!("invalid rate limit '{value}', expected e.g. 60/m, 300/5m or 1000/h");
+        let (capacity, period) = value.trim().split_once('/').ok_or_else(invalid)?;
+        let capacity: u32 = capacity.trim().parse().ok().filter(|n| *n > 0).ok_or_else(invalid)?;
+
+        let period = period.trim();
+        let unit_at = period.char_indices().last().map_or(0, |(i, _)| i);
+        let (amount, unit) = period.split_at(unit_at);
+        let amount: u64 = if amount.is_empty() { 1 } else { amount.parse().map_err(|_| invalid())? };
+        let secs = match unit {
+            "s" => amount,
+            "m" => amount * 60,
+            "h" => amount * 60 * 60,
+            _ => return Err(invalid()),
+        };
+        if secs == 0 {
+            return Err(invalid());
+        }
+        Ok(Self { capacity, period: Duration::from_secs(secs) })
+    }
+}
+
+impl FromStr for Limit {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::parse(value)
+    }
+}
+
+impl TryFrom<String> for Limit {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value)
+    }
+}
+
+/// Hasil satu cek rate limit.
+#[derive(Debug, Clone, Copy)]
+pub struct Decision {
+    pub allowed: bool,
+    pub limit: u32,
+    pub remaining: u32,
+    // Detik sampai request berikutnya boleh (0 kalau allowed)
+    pub retry_after: u64,
+    // Detik sampai bucket penuh lagi
+    pub reset_after: u64,
+}
+
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+    limit: Limit,
+}
+
+/// Token bucket di memori, satu bucket per kunci (mis. "key:12", "auth:ip:10.0.0.1"). Hilang saat server restart.
+#[derive(Clone, Default)]
+pub struct RateLimiter {
+    buckets: Arc<Mutex<HashMap<String, Bucket>>>,
+}
+
+impl RateLimiter {
+    /// Ambil satu token dari bucket `key`. Bucket baru mulai dalam keadaan penuh.
+    pub fn check(&self, key: &str, limit: Limit) -> Decision {
+        let now = Instant::now();
+        let capacity = limit.capacity as f64;
+        let rate = limit.rate();
+
+        let mut buckets = self.buckets.lock().unwrap_or_else(PoisonError::into_inner);
+        let bucket = buckets
+            .entry(key.to_string())
+            .or_insert(Bucket { tokens: capacity, updated_at: now, limit });
+
+        let elapsed = now.duration_since(bucket.updated_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(capacity);
+        bucket.updated_at = now;
+        // Batas bisa berubah (mis. admin mengubah limit key), bucket ikut batas terbaru
+        bucket.limit = limit;
+
+        let allowed = bucket.tokens >= 1.0;
+        if allowed {
+            bucket.tokens -= 1.0;
+        }
+
+        Decision {
+            allowed,
+            limit: limit.capacity,
+            remaining: bucket.tokens.floor() as u32,
+            retry_after: if allowed { 0 } else { ((1.0 - bucket.tokens) / rate).ceil().max(1.0) as u64 },
+            reset_after: ((capacity - bucket.tokens) / rate).ceil() as u64,
+        }
+    }
+
+    // Bucket yang sudah penuh lagi sama saja dengan bucket baru, jadi boleh dihapus
+    fn prune(&self) {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap_or_else(PoisonError::into_inner);
+        buckets.retain(|_, bucket| {
+            let elapsed = now.duration_since(bucket.updated_at).as_secs_f64();
+            bucket.tokens + elapsed * bucket.limit.rate() < bucket.limit.capacity as f64
+        });
+    }
+}
+
+/// Kelompok route, masing-masing punya batas sendiri.
+#[derive(Debug, Clone, Copy)]
+enum Group {
+    Auth,
+    Read,
+    Write,
+}
+
+impl Group {
+    fn for_request(method: &Method, path: &str) -> Self {
+        if AUTH_PATHS.contains(&path) {
+            Group::Auth
+        } else if matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS) {
+            Group::Read
+        } else {
+            Group::Write
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Group::Auth => "auth",
+            Group::Read => "read",
+            Group::Write => "write",
+        }
+    }
+}
+
+/// Batas per kelompok route, bagian `[rate_limit]` di config. `*_user` dipakai untuk request dengan token
+/// (per akun), selain itu per IP. Kelompok auth selalu per IP karena belum punya token.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimitConfig {
+    pub enabled: bool,
+    pub auth: Limit,
+    pub read: Limit,
+    pub read_user: Limit,
+    pub write: Limit,
+    pub write_user: Limit,
+    // Request dengan X-API-Key per IP, dicek sebelum key dicari di database (tebak key / banjir lookup)
+    pub api_key_ip: Limit,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            auth: Limit::per_minute(20),
+            read: Limit::per_minute(300),
+            read_user: Limit::per_minute(600),
+            write: Limit::per_minute(60),
+            write_user: Limit::per_minute(120),
+            api_key_ip: Limit::per_minute(600),
+        }
+    }
+}
+
+impl RateLimitConfig {
+    // (limit per IP, limit per akun)
+    fn policy(&self, group: Group) -> (Limit, Option<Limit>) {
+        match group {
+            Group::Auth => (self.auth, None),
+            Group::Read => (self.read, Some(self.read_user)),
+            Group::Write => (self.write, Some(self.write_user)),
+        }
+    }
+}
+
+fn apply_headers(headers: &mut HeaderMap, decision: &Decision, limit: Limit) {
+    headers.insert(RATELIMIT_LIMIT, HeaderValue::from(decision.limit));
+    headers.insert(RATELIMIT_REMAINING, HeaderValue::from(decision.remaining));
+    headers.insert(RATELIMIT_RESET, HeaderValue::from(decision.reset_after));
+    if let Ok(policy) = HeaderValue::from_str(&format!("{};w={}", limit.capacity, limit.period.as_secs())) {
+        headers.insert(RATELIMIT_POLICY, policy);
+    }
+}
+
+// Middleware: request dengan API key pakai limit key itu sendiri (IP-nya sudah dicek di api_key_middleware). Selain itu per kelompok route,
+// per akun kalau ada token valid (banyak user bisa berbagi satu IP lewat NAT), kalau tidak per IP.
+pub async fn rate_limit_middleware(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    request: Request,
+    next: Next,
+) -> Response {
+    let (bucket, limit) = if let Some(key) = request.extensions().get::<ApiKeyClient>() {
+        (format!("key:{}", key.id), Limit::per_minute(key.rate_limit_per_minute.max(1) as u32))
+    } else {
+        if !state.rate_limit.enabled {
+            return next.run(request).await;
+        }
+        let group = Group::for_request(request.method(), request.uri().path());
+        let (per_ip, per_user) = state.rate_limit.policy(group);
+        match (per_user, auth::token_account(&state.jwt, request.headers())) {
+            (Some(limit), Some((role, id))) => (format!("{}:{role}:{id}", group.as_str()), limit),
+            _ => {
+                let ip = client.ip.as_deref().unwrap_or("unknown");
+                (format!("{}:ip:{ip}", group.as_str()), per_ip)
+            }
+        }
+    };
+
+    let decision = state.rate_limiter.check(&bucket, limit);
+    let mut response = if decision.allowed { next.run(request).await } else { too_many_requests(&decision) };
+    apply_headers(response.headers_mut(), &decision, limit);
+    response
+}
+
+fn too_many_requests(decision: &Decision) -> Response {
+    AppError::TooManyRequests {
+        message: "Terlalu banyak request, coba lagi nanti".to_string(),
+        retry_after: decision.retry_after,
+    }
+    .into_response()
+}
+
+/// Dipanggil api_key_middleware sebelum key dicari: semua request ber-X-API-Key dihitung per IP
+/// (`api_key_ip`), valid atau tidak. Selalu aktif, sama seperti limit per key. Some = response 429.
+pub fn check_api_key_ip(state: &AppState, client: &ClientInfo) -> Option<Response> {
+    let limit = state.rate_limit.api_key_ip;
+    let ip = client.ip.as_deref().unwrap_or("unknown");
+    let decision = state.rate_limiter.check(&format!("api_key:ip:{ip}"), limit);
+    if decision.allowed {
+        return None;
+    }
+    let mut response = too_many_requests(&decision);
+    apply_headers(response.headers_mut(), &decision, limit);
+    Some(response)
+}
+
+/// Tiap 10 menit: buang bucket yang sudah penuh supaya map tidak terus membesar.
+pub fn spawn_cleanup_task(limiter: RateLimiter) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(CLEANUP_INTERVAL);
+        loop {
+            interval.tick().await;
+            limiter.prune();
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Mundurkan waktu bucket seolah `secs` detik sudah lewat
+    fn age_bucket(limiter: &RateLimiter, key: &str, secs: u64) {
+        let mut buckets = limiter.buckets.lock().unwrap();
+        let bucket = buckets.get_mut(key).unwrap();
+        bucket.updated_at = bucket.updated_at.checked_sub(Duration::from_secs(secs)).unwrap();
+    }
+
+    #[test]
+    fn parses_valid_limits() {
+        let limit = Limit::parse("20/m").unwrap();
+        assert_eq!((limit.capacity, limit.period), (20, Duration::from_secs(60)));
+        let limit = Limit::parse("300/5m").unwrap();
+        assert_eq!((limit.capacity, limit.period), (300, Duration::from_secs(300)));
+        let limit = Limit::parse("1000/h").unwrap();
+        assert_eq!((limit.capacity, limit.period), (1000, Duration::from_secs(3600)));
+        let limit = Limit::parse(" 5 / 30s ").unwrap();
+        assert_eq!((limit.capacity, limit.period), (5, Duration::from_secs(30)));
+    }
+
+    #[test]
+    fn rejects_malformed_limits() {
+        for value in ["", "60", "/m", "0/m", "-1/m", "60/x", "60/", "60/0s", "60/5", "abc/m", "60/m/h", "60/1.5m"] {
+            assert!(Limit::parse(value).is_err(), "'{value}' should be rejected");
+        }
+    }
+
+    #[test]
+    fn bucket_starts_full_and_then_rejects() {
+        let limiter = RateLimiter::default();
+        let limit = Limit::parse("3/m").unwrap();
+
+        for remaining in [2, 1, 0] {
+            let decision = limiter.check("k", limit);
+            assert!(decision.allowed);
+            assert_eq!(decision.remaining, remaining);
+            assert_eq!(decision.retry_after, 0);
+        }
+
+        let decision = limiter.check("k", limit);
+        assert!(!decision.allowed);
+        assert_eq!(decision.limit, 3);
+        // 3 token per 60 detik -> satu token tiap 20 detik
+        assert_eq!(decision.retry_after, 20);
+        assert_eq!(decision.reset_after, 60);
+    }
+
+    #[test]
+    fn bucket_refills_over_time() {
+        let limiter = RateLimiter::default();
+        let limit = Limit::parse("3/m").unwrap();
+        for _ in 0..3 {
+            limiter.check("k", limit);
+        }
+        assert!(!limiter.check("k", limit).allowed);
+
+        age_bucket(&limiter, "k", 20);
+        assert!(limiter.check("k", limit).allowed);
+        assert!(!limiter.check("k", limit).allowed);
+
+        // Lama tidak dipakai: terisi penuh lagi, tidak lebih dari kapasitas
+        age_bucket(&limiter, "k", 600);
+        let decision = limiter.check("k", limit);
+        assert!(decision.allowed);
+        assert_eq!(decision.remaining, 2);
+    }
+
+    #[test]
+    fn buckets_are_independent_per_key() {
+        let limiter = RateLimiter::default();
+        let limit = Limit::parse("1/m").unwrap();
+        assert!(limiter.check("auth:ip:10.0.0.1", limit).allowed);
+        assert!(!limiter.check("auth:ip:10.0.0.1", limit).allowed);
+        assert!(limiter.check("auth:ip:10.0.0.2", limit).allowed);
+    }
+
+    #[test]
+    fn prune_drops_only_full_buckets() {
+        let limiter = RateLimiter::default();
+        let limit = Limit::parse("2/m").unwrap();
+        limiter.check("old", limit);
+        limiter.check("new", limit);
+        age_bucket(&limiter, "old", 60);
+
+        limiter.prune();
+        let buckets = limiter.buckets.lock().unwrap();
+        assert!(!buckets.contains_key("old"));
+        assert!(buckets.contains_key("new"));
+    }
+}
@@ -0,0 +1,37 @@
+use axum::{
+    extract::Request,
+    http::HeaderValue,
+    middleware::Next,
+    response::Response,
+};
+use uuid::Uuid;
+
+use crate::headers::REQUEST_ID_HEADER;
+
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// Request id yang sedang diproses (kosong kalau dipanggil di luar request).
+pub fn current() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+// Middleware: pakai X-Request-Id dari client/proxy kalau ada, kalau tidak buat UUID baru.
+// Id yang sama dikirim balik di header response dan di body error.
+pub async fn request_id_middleware(request: Request, next: Next) -> Response {
+    let id = request
+        .headers()
+        .get(&REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .filter(|v| !v.is_empty() && v.len() <= 128)
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    let mut response = REQUEST_ID.scope(id.clone(), next.run(request)).await;
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
@@ -0,0 +1,245 @@
+use axum::{extract::State, Json};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{FromRow, PgConnection};
+
+use crate::app_state::AppState;
+use crate::auth::AdminUser;
+use crate::content::find_table;
+use crate::error::{AppError, AppJson, AppPath, AppQuery, AppResult};
+use crate::slug;
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 200;
+
+// Berubah di setiap tulis, tidak perlu ditampilkan di diff
+const DIFF_IGNORED: &[&str] = &["updated_at", "version"];
+// Kolom yang diatur server, tidak ikut di-rollback (slug menyesuaikan judul, sampah lewat restore)
+const SYSTEM_COLUMNS: &[&str] = &["id", "slug", "updated_at", "version", "deleted_at"];
+
+#[derive(Debug, Clone, Copy)]
+pub enum Action {
+    Create,
+    Update,
+    Delete,
+    Restore,
+    Rollback,
+}
+
+impl Action {
+    fn as_str(self) -> &'static str {
+        match self {
+            Action::Create => "create",
+            Action::Update => "update",
+            Action::Delete => "delete",
+            Action::Restore => "restore",
+            Action::Rollback => "rollback",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct Revision {
+    pub id: i64,
+    pub table_name: String,
+    pub row_id: i32,
+    pub action: String,
+    pub admin_id: Option<i32>,
+    pub admin_username: Option<String>,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: Value,
+    pub after: Value,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RevisionView {
+    #[serde(flatten)]
+    pub revision: Revision,
+    pub diff: Vec<FieldChange>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HistoryQuery {
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RollbackRequest {
+    pub revision_id: i64,
+}
+
+/// Isi baris sekarang sebagai JSON (semua kolom), dipakai untuk nilai before/after.
+/// Baris dikunci (FOR UPDATE) sampai transaksi selesai, jadi `before` pasti versi yang ditimpa.
+pub async fn snapshot(conn: &mut PgConnection, table: &'static str, id: i32) -> Result<Option<Value>, sqlx::Error> {
+    sqlx::query_scalar(&format!("SELECT to_jsonb(t) FROM {table} t WHERE t.id = $1 FOR UPDATE"))
+        .bind(id)
+        .fetch_optional(conn)
+        .await
+}
+
+/// Catat satu revisi. Nilai `after` diambil langsung dari isi baris sekarang,
+/// jadi harus dipanggil di transaksi yang sama dengan tulisannya.
+pub async fn record(
+    conn: &mut PgConnection,
+    table: &'static str,
+    id: i32,
+    action: Action,
+    admin: &AdminUser,
+    before: Option<Value>,
+) -> Result<Revision, sqlx::Error> {
+    sqlx::query_as::<_, Revision>(&format!(
+        "INSERT INTO revisions (table_name, row_id, action, admin_id, admin_username, before, after)
+         VALUES ($1, $2, $3, $4, $5, $6, (SELECT to_jsonb(t) FROM {table} t WHERE t.id = $2))
+         RETURNING *"
+    ))
+    .bind(table)
+    .bind(id)
+    .bind(action.as_str())
+    .bind(admin.id)
+    .bind(&admin.username)
+    .bind(before)
+    .fetch_one(conn)
+    .await
+}
+
+// Field yang berubah antara before dan after
+fn diff(before: Option<&Value>, after: Option<&Value>) -> Vec<FieldChange> {
+    let empty = serde_json::Map::new();
+    let before = before.and_then(Value::as_object).unwrap_or(&empty);
+    let after = after.and_then(Value::as_object).unwrap_or(&empty);
+
+    let mut fields: Vec<&String> = before.keys().chain(after.keys()).collect();
+    fields.sort();
+    fields.dedup();
+
+    fields
+        .into_iter()
+        .filter(|field| !DIFF_IGNORED.contains(&field.as_str()))
+        .filter_map(|field| {
+            let old = before.get(field).cloned().unwrap_or(Value::Null);
+            let new = after.get(field).cloned().unwrap_or(Value::Null);
+            (old != new).then(|| FieldChange { field: field.clone(), before: old, after: new })
+        })
+        .collect()
+}
+
+fn with_diff(revision: Revision) -> RevisionView {
+    let diff = diff(revision.before.as_ref(), revision.after.as_ref());
+    RevisionView { revision, diff }
+}
+
+// GET /api/revisions/{table}/{id}?limit=50 -> riwayat perubahan satu data, terbaru dulu
+pub async fn get_history(
+    State(state): State<AppState>,
+    _admin: AdminUser,
+    AppPath((table, id)): AppPath<(String, i32)>,
+    AppQuery(query): AppQuery<HistoryQuery>,
+) -> AppResult<Json<Vec<RevisionView>>> {
+    let (table, _) = find_table(&table)?;
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let rows = sqlx::query_as::<_, Revision>(
+        "SELECT * FROM revisions WHERE table_name = $1 AND row_id = $2 ORDER BY id DESC LIMIT $3",
+    )
+    .bind(table)
+    .bind(id)
+    .bind(limit)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(rows.into_iter().map(with_diff).collect()))
+}
+
+// POST /api/revisions/{table}/{id}/rollback { "revision_id": 12 }
+// Isi data dikembalikan seperti sesudah revisi tersebut. Rollback sendiri dicatat sebagai revisi baru.
+pub async fn rollback(
+    State(state): State<AppState>,
+    admin: AdminUser,
+    AppPath((table, id)): AppPath<(String, i32)>,
+    AppJson(payload): AppJson<RollbackRequest>,
+) -> AppResult<Json<RevisionView>> {
+    let (table, title_column) = find_table(&table)?;
+
+    let target = sqlx::query_as::<_, Revision>(
+        "SELECT * FROM revisions WHERE id = $1 AND table_name = $2 AND row_id = $3",
+    )
+    .bind(payload.revision_id)
+    .bind(table)
+    .bind(id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::not_found("Revisi tidak ditemukan"))?;
+
+    let values = target
+        .after
+        .ok_or_else(|| AppError::not_found("Revisi ini tidak punya isi untuk dikembalikan"))?;
+
+    // Hanya kolom yang masih ada di tabel (nama kolom di-interpolasi ke SQL)
+    let existing: Vec<String> = sqlx::query_scalar(
+        "SELECT column_name::text FROM information_schema.columns WHERE table_schema = current_schema() AND table_name = $1",
+    )
+    .bind(table)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let columns: Vec<&String> = existing
+        .iter()
+        .filter(|c| !SYSTEM_COLUMNS.contains(&c.as_str()))
+        .filter(|c| values.get(c.as_str()).is_some())
+        .collect();
+
+    // Snapshot, isi kolom, slug (+ redirect) dan revisi satu transaksi, diulang kalau slug keburu dipakai
+    let title = values.get(title_column).and_then(Value::as_str);
+    let mut attempt = 1;
+    let latest = loop {
+        let mut tx = state.pool.begin().await?;
+        let result = async {
+            let before = snapshot(&mut tx, table, id)
+                .await?
+                .filter(|row| row.get("deleted_at").is_some_and(Value::is_null))
+                .ok_or_else(|| AppError::not_found("Data tidak ditemukan atau ada di tempat sampah"))?;
+
+            if !columns.is_empty() {
+                let list = columns.iter().map(|c| format!("\"{c}\"")).collect::<Vec<_>>().join(", ");
+                sqlx::query(&format!(
+                    "UPDATE {table} SET ({list}) = (SELECT {list} FROM jsonb_populate_record(NULL::{table}, $1)),
+                            updated_at = now(), version = version + 1
+                     WHERE id = $2"
+                ))
+                .bind(&values)
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+            }
+
+            // Judul ikut kembali -> slug disesuaikan (slug lama tetap di-redirect)
+            if let Some(title) = title
+                && let Some(new_slug) = slug::slug_for_update(&mut tx, table, id, title).await?
+            {
+                sqlx::query(&format!("UPDATE {table} SET slug = $1 WHERE id = $2"))
+                    .bind(new_slug)
+                    .bind(id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+
+            Ok(record(&mut tx, table, id, Action::Rollback, &admin, Some(before)).await?)
+        }
+        .await;
+        if let Some(latest) = slug::finish(tx, result, &mut attempt).await? {
+            break latest;
+        }
+    };
+
+    state.sitemap.invalidate();
+
+    Ok(Json(with_diff(latest)))
+}
@@ -0,0 +1,355 @@
+use axum::{extract::State, http::StatusCode, Json};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use sqlx::{FromRow, PgConnection, PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::app_state::AppState;
+use crate::audit::{self, AuditEvent};
+use crate::auth::{self, CurrentSession, TokenResponse};
+use crate::client_info::ClientInfo;
+use crate::error::{AppError, AppJson, AppPath, AppResult};
+
+// Refresh token berlaku 30 hari sejak dipakai terakhir. Tiap refresh dapat token baru (rotasi)
+pub const REFRESH_TTL_DAYS: i64 = 30;
+// last_used_at cukup diperbarui paling sering tiap 5 menit, supaya tidak menulis ke database tiap request
+const TOUCH_INTERVAL_SECS: i64 = 5 * 60;
+const CLEANUP_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+#[derive(Deserialize)]
+pub struct RefreshRequest {
+    pub refresh_token: String,
+}
+
+/// Satu perangkat / browser yang sedang login.
+#[derive(Debug, Serialize, FromRow)]
+pub struct SessionInfo {
+    pub id: String,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    // true untuk sesi yang dipakai request ini
+    #[sqlx(default)]
+    pub current: bool,
+}
+
+#[derive(FromRow)]
+struct RefreshRow {
+    session_id: String,
+    used_at: Option<DateTime<Utc>>,
+    role: String,
+    account_id: i32,
+    username: String,
+    mfa: bool,
+    active: bool,
+}
+
+fn hash_refresh_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.trim().as_bytes()))
+}
+
+// 244 bit acak, di database hanya disimpan hash SHA-256-nya
+fn new_refresh_token() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+async fn issue_refresh_token<'c>(db: impl PgExecutor<'c>, session_id: &str) -> Result<String, sqlx::Error> {
+    let token = new_refresh_token();
+    sqlx::query("INSERT INTO refresh_tokens (token_hash, session_id) VALUES ($1, $2)")
+        .bind(hash_refresh_token(&token))
+        .bind(session_id)
+        .execute(db)
+        .await?;
+    Ok(token)
+}
+
+fn token_response(
+    state: &AppState,
+    session_id: &str,
+    role: &str,
+    id: i32,
+    username: &str,
+    mfa: bool,
+    refresh_token: String,
+) -> AppResult<TokenResponse> {
+    Ok(TokenResponse {
+        token: auth::access_token(&state.jwt, session_id, id, username, role, mfa)?,
+        token_type: "Bearer",
+        expires_in: state.jwt.ttl_minutes * 60,
+        role: role.to_string(),
+        refresh_token,
+        refresh_expires_in: REFRESH_TTL_DAYS * 24 * 60 * 60,
+    })
+}
+
+/// Login berhasil -> sesi baru untuk perangkat ini + token akses dan refresh token.
+pub async fn start(
+    state: &AppState,
+    client: &ClientInfo,
+    role: &str,
+    id: i32,
+    username: &str,
+    mfa: bool,
+) -> AppResult<TokenResponse> {
+    let mut tx = state.pool.begin().await?;
+    let token = start_in(&mut tx, state, client, role, id, username, mfa).await?;
+    tx.commit().await?;
+    Ok(token)
+}
+
+/// Sama dengan `start`, di dalam transaksi pemanggil (mis. sesi diganti saat 2FA diaktifkan).
+pub async fn start_in(
+    conn: &mut PgConnection,
+    state: &AppState,
+    client: &ClientInfo,
+    role: &str,
+    id: i32,
+    username: &str,
+    mfa: bool,
+) -> AppResult<TokenResponse> {
+    let session_id = Uuid::new_v4().to_string();
+    sqlx::query(
+        "INSERT INTO sessions (id, role, account_id, username, mfa, ip, user_agent, expires_at)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+    )
+    .bind(&session_id)
+    .bind(role)
+    .bind(id)
+    .bind(username)
+    .bind(mfa)
+    .bind(&client.ip)
+    .bind(&client.user_agent)
+    .bind(Utc::now() + Duration::days(REFRESH_TTL_DAYS))
+    .execute(&mut *conn)
+    .await?;
+
+    let refresh_token = issue_refresh_token(&mut *conn, &session_id).await?;
+    token_response(state, &session_id, role, id, username, mfa, refresh_token)
+}
+
+/// Dicek di setiap request yang memakai token akses: sesi harus masih aktif (belum logout / dicabut).
+pub async fn touch(pool: &PgPool, session_id: &str, role: &str, account_id: i32) -> Result<bool, sqlx::Error> {
+    let last_used: Option<DateTime<Utc>> = sqlx::query_scalar(
+        "SELECT last_used_at FROM sessions
+         WHERE id = $1 AND role = $2 AND account_id = $3 AND revoked_at IS NULL AND expires_at > now()",
+    )
+    .bind(session_id)
+    .bind(role)
+    .bind(account_id)
+    .fetch_optional(pool)
+    .await?;
+
+    let Some(last_used) = last_used else { return Ok(false) };
+    if Utc::now() - last_used > Duration::seconds(TOUCH_INTERVAL_SECS) {
+        sqlx::query("UPDATE sessions SET last_used_at = now() WHERE id = $1")
+            .bind(session_id)
+            .execute(pool)
+            .await?;
+    }
+    Ok(true)
+}
+
+/// Cabut satu sesi. Token akses dan refresh token sesi itu langsung tidak berlaku.
+pub async fn revoke<'c>(db: impl PgExecutor<'c>, session_id: &str, reason: &str) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        "UPDATE sessions SET revoked_at = now(), revoked_reason = $2 WHERE id = $1 AND revoked_at IS NULL",
+    )
+    .bind(session_id)
+    .bind(reason)
+    .execute(db)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Cabut semua sesi sebuah akun (password diganti, akun dihapus, ...), kecuali `except` kalau diisi.
+pub async fn revoke_all<'c>(
+    db: impl PgExecutor<'c>,
+    role: &str,
+    account_id: i32,
+    except: Option<&str>,
+    reason: &str,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        "UPDATE sessions SET revoked_at = now(), revoked_reason = $4
+         WHERE role = $1 AND account_id = $2 AND revoked_at IS NULL AND id IS DISTINCT FROM $3",
+    )
+    .bind(role)
+    .bind(account_id)
+    .bind(except)
+    .bind(reason)
+    .execute(db)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+// POST /refresh { "refresh_token": "..." } -> token akses + refresh token baru, yang lama tidak berlaku lagi.
+// Refresh token yang sudah pernah dipakai ditunjukkan lagi = kemungkinan dicuri -> seluruh sesi dicabut.
+pub async fn refresh(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    AppJson(payload): AppJson<RefreshRequest>,
+) -> AppResult<Json<TokenResponse>> {
+    let invalid = || AppError::Unauthorized("Refresh token tidak valid atau sesi sudah berakhir, silakan login ulang".to_string());
+    let token_hash = hash_refresh_token(&payload.refresh_token);
+
+    // Klaim token lama, perpanjang sesi dan token baru dalam satu transaksi: kalau ada yang gagal, token lama
+    // tetap belum terpakai dan client bisa mencoba lagi tanpa dianggap memakai ulang token
+    let mut tx = state.pool.begin().await?;
+    let row = sqlx::query_as::<_, RefreshRow>(
+        "SELECT r.session_id, r.used_at, s.role, s.account_id, s.username, s.mfa,
+                (s.revoked_at IS NULL AND s.expires_at > now()) AS active
+         FROM refresh_tokens r JOIN sessions s ON s.id = r.session_id
+         WHERE r.token_hash = $1",
+    )
+    .bind(&token_hash)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(invalid)?;
+
+    if !row.active {
+        return Err(invalid());
+    }
+
+    // Tandai terpakai secara atomik: dari dua request dengan token yang sama, hanya satu yang lolos
+    // (request kedua menunggu kunci baris sampai transaksi pertama selesai)
+    let claimed = sqlx::query("UPDATE refresh_tokens SET used_at = now() WHERE token_hash = $1 AND used_at IS NULL")
+        .bind(&token_hash)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected()
+        > 0;
+
+    if row.used_at.is_some() || !claimed {
+        revoke(&mut *tx, &row.session_id, "refresh_token_reused").await?;
+        let event = format!("{}.refresh_token_reused", row.role);
+        let failure = AuditEvent::failure(&event)
+            .actor(Some(row.account_id), &row.username)
+            .details(json!({ "session_id": row.session_id }));
+        audit::record(&mut *tx, &client, failure).await?;
+        tx.commit().await?;
+        return Err(invalid());
+    }
+
+    sqlx::query("UPDATE sessions SET last_used_at = now(), expires_at = $2, ip = $3, user_agent = $4 WHERE id = $1")
+        .bind(&row.session_id)
+        .bind(Utc::now() + Duration::days(REFRESH_TTL_DAYS))
+        .bind(&client.ip)
+        .bind(&client.user_agent)
+        .execute(&mut *tx)
+        .await?;
+
+    let refresh_token = issue_refresh_token(&mut *tx, &row.session_id).await?;
+    let token = token_response(
+        &state,
+        &row.session_id,
+        &row.role,
+        row.account_id,
+        &row.username,
+        row.mfa,
+        refresh_token,
+    )?;
+    tx.commit().await?;
+    Ok(Json(token))
+}
+
+// POST /logout -> cabut sesi yang sedang dipakai (token user maupun admin)
+pub async fn logout(State(state): State<AppState>, session: CurrentSession, client: ClientInfo) -> AppResult<StatusCode> {
+    revoke(&state.pool, &session.id, "logout").await?;
+
+    let event = format!("{}.logout", session.role);
+    let event = AuditEvent::success(&event).actor(Some(session.account_id), &session.username);
+    audit::record(&state.pool, &client, event).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// GET /api/sessions -> sesi aktif akun yang login, terbaru dipakai dulu
+pub async fn list_sessions(
+    State(state): State<AppState>,
+    session: CurrentSession,
+) -> AppResult<Json<Vec<SessionInfo>>> {
+    let sessions = sqlx::query_as::<_, SessionInfo>(
+        "SELECT id, ip, user_agent, created_at, last_used_at, expires_at, id = $3 AS current
+         FROM sessions
+         WHERE role = $1 AND account_id = $2 AND revoked_at IS NULL AND expires_at > now()
+         ORDER BY last_used_at DESC",
+    )
+    .bind(&session.role)
+    .bind(session.account_id)
+    .bind(&session.id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(sessions))
+}
+
+// DELETE /api/sessions/{id} -> logout dari perangkat lain
+pub async fn revoke_session(
+    State(state): State<AppState>,
+    session: CurrentSession,
+    client: ClientInfo,
+    AppPath(id): AppPath<String>,
+) -> AppResult<StatusCode> {
+    let revoked = sqlx::query(
+        "UPDATE sessions SET revoked_at = now(), revoked_reason = 'revoked_by_owner'
+         WHERE id = $1 AND role = $2 AND account_id = $3 AND revoked_at IS NULL",
+    )
+    .bind(&id)
+    .bind(&session.role)
+    .bind(session.account_id)
+    .execute(&state.pool)
+    .await?
+    .rows_affected();
+    if revoked == 0 {
+        return Err(AppError::not_found("Sesi tidak ditemukan atau sudah berakhir"));
+    }
+
+    let event = format!("{}.session_revoked", session.role);
+    let event = AuditEvent::success(&event)
+        .actor(Some(session.account_id), &session.username)
+        .details(json!({ "session_id": id }));
+    audit::record(&state.pool, &client, event).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// DELETE /api/sessions -> logout dari semua perangkat lain, sesi ini tetap aktif
+pub async fn revoke_other_sessions(
+    State(state): State<AppState>,
+    session: CurrentSession,
+    client: ClientInfo,
+) -> AppResult<StatusCode> {
+    let revoked = revoke_all(&state.pool, &session.role, session.account_id, Some(&session.id), "revoked_by_owner").await?;
+
+    let event = format!("{}.session_revoked", session.role);
+    let event = AuditEvent::success(&event)
+        .actor(Some(session.account_id), &session.username)
+        .details(json!({ "others": revoked }));
+    audit::record(&state.pool, &client, event).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Tiap jam: hapus sesi yang sudah berakhir lebih dari 30 hari (refresh token ikut lewat ON DELETE CASCADE).
+pub fn spawn_cleanup_task(pool: PgPool) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(CLEANUP_INTERVAL);
+        loop {
+            interval.tick().await;
+            let result = sqlx::query(
+                "DELETE FROM sessions
+                 WHERE COALESCE(revoked_at, expires_at) < now() - make_interval(days => $1)",
+            )
+            .bind(REFRESH_TTL_DAYS as i32)
+            .execute(&pool)
+            .await;
+            if let Err(e) = result {
+                tracing::error!("Failed to clean up sessions: {:?}", e);
+            }
+        }
+    });
+}
@@ -0,0 +1,189 @@
+use axum::{
+    extract::State,
+    http::header,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgPool};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+use crate::app_state::AppState;
+use crate::error::{AppError, AppPath, AppResult};
+use crate::feed::escape;
+
+// Batas URL per file sitemap menurut sitemaps.org
+pub const DEFAULT_MAX_URLS: usize = 50_000;
+const CACHE_CONTROL: &str = "public, max-age=600";
+
+// Halaman statis frontend yang selalu ada
+const STATIC_PAGES: &[&str] = &["/", "/wisata", "/cafes", "/trans"];
+
+/// Cara frontend mengenali satu data di URL halaman detail.
+enum DetailKey {
+    // "<PREFIX>-<slug>" (ALAM-curug-cipendok, ...). Prefix wajib karena satu route frontend melayani dua tabel
+    // (slug hanya unik per tabel). Lihat WisataDetail / CafeDetail
+    PrefixedSlug(&'static str),
+    Slug,
+}
+
+// (tabel, path halaman detail di frontend, kunci di URL). Harus sama dengan route di FrontEnd/src/App.tsx
+const DETAIL_PAGES: &[(&str, &str, DetailKey)] = &[
+    ("wisata_alam", "/wisata", DetailKey::PrefixedSlug("ALAM")),
+    ("wisata_pendidikan", "/wisata", DetailKey::PrefixedSlug("EDU")),
+    ("tempat_nongkrong", "/cafes", DetailKey::PrefixedSlug("CAFE")),
+    ("kuliner", "/cafes", DetailKey::PrefixedSlug("KUL")),
+    ("news", "/news", DetailKey::Slug),
+];
+
+#[derive(Debug, Clone)]
+pub struct SitemapEntry {
+    pub path: String,
+    pub lastmod: Option<DateTime<Utc>>,
+}
+
+#[derive(FromRow)]
+struct DetailRow {
+    slug: String,
+    updated_at: DateTime<Utc>,
+}
+
+/// Cache daftar URL sitemap. Handler yang mengubah data cukup panggil `invalidate()`,
+/// request berikutnya akan membangun ulang dari database.
+#[derive(Clone)]
+pub struct SitemapCache {
+    entries: Arc<RwLock<Option<Arc<Vec<SitemapEntry>>>>>,
+    // Naik di setiap invalidate. Hasil load yang mulai sebelum invalidate tidak disimpan ke cache
+    generation: Arc<AtomicU64>,
+    max_urls: usize,
+}
+
+impl SitemapCache {
+    pub fn new(max_urls: usize) -> Self {
+        Self {
+            entries: Arc::new(RwLock::new(None)),
+            generation: Arc::new(AtomicU64::new(0)),
+            max_urls: max_urls.clamp(1, DEFAULT_MAX_URLS),
+        }
+    }
+
+    pub fn invalidate(&self) {
+        if let Ok(mut guard) = self.entries.write() {
+            self.generation.fetch_add(1, Ordering::SeqCst);
+            *guard = None;
+        }
+    }
+
+    async fn load(&self, pool: &PgPool) -> Result<Arc<Vec<SitemapEntry>>, sqlx::Error> {
+        if let Some(cached) = self.entries.read().ok().and_then(|guard| guard.clone()) {
+            return Ok(cached);
+        }
+        let generation = self.generation.load(Ordering::SeqCst);
+
+        let mut entries: Vec<SitemapEntry> = STATIC_PAGES
+            .iter()
+            .map(|path| SitemapEntry { path: path.to_string(), lastmod: None })
+            .collect();
+
+        for (table, prefix, key) in DETAIL_PAGES {
+            let rows = sqlx::query_as::<_, DetailRow>(&format!(
+                "SELECT slug, updated_at FROM {table} WHERE deleted_at IS NULL ORDER BY id"
+            ))
+            .fetch_all(pool)
+            .await?;
+            entries.extend(rows.into_iter().map(|row| SitemapEntry {
+                path: match key {
+                    DetailKey::PrefixedSlug(key_prefix) => format!("{prefix}/{key_prefix}-{}", row.slug),
+                    DetailKey::Slug => format!("{prefix}/{}", row.slug),
+                },
+                lastmod: Some(row.updated_at),
+            }));
+        }
+
+        // Ada invalidate selama query berjalan -> hasil ini mungkin sudah basi, jangan di-cache
+        let entries = Arc::new(entries);
+        if let Ok(mut guard) = self.entries.write()
+            && self.generation.load(Ordering::SeqCst) == generation
+        {
+            *guard = Some(entries.clone());
+        }
+        Ok(entries)
+    }
+}
+
+// GET /sitemap.xml -> urlset, atau sitemapindex kalau URL melebihi batas per file
+pub async fn sitemap_index(State(state): State<AppState>) -> AppResult<Response> {
+    let entries = state.sitemap.load(&state.pool).await?;
+
+    let max = state.sitemap.max_urls;
+    if entries.len() <= max {
+        return Ok(xml_response(render_urlset(&state.frontend_url, &entries)));
+    }
+
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<sitemapindex xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n");
+    for (i, chunk) in entries.chunks(max).enumerate() {
+        xml.push_str("  <sitemap>\n");
+        xml.push_str(&format!(
+            "    <loc>{}</loc>\n",
+            escape(&format!("{}/sitemaps/sitemap-{}.xml", state.api_url, i + 1))
+        ));
+        if let Some(lastmod) = chunk.iter().filter_map(|e| e.lastmod).max() {
+            xml.push_str(&format!("    <lastmod>{}</lastmod>\n", lastmod.to_rfc3339()));
+        }
+        xml.push_str("  </sitemap>\n");
+    }
+    xml.push_str("</sitemapindex>\n");
+
+    Ok(xml_response(xml))
+}
+
+// GET /sitemaps/sitemap-{n}.xml (n mulai dari 1)
+pub async fn sitemap_page(
+    State(state): State<AppState>,
+    AppPath(file): AppPath<String>,
+) -> AppResult<Response> {
+    let page: usize = file
+        .strip_prefix("sitemap-")
+        .and_then(|rest| rest.strip_suffix(".xml"))
+        .and_then(|n| n.parse().ok())
+        .filter(|n| *n >= 1)
+        .ok_or_else(|| AppError::not_found("Sitemap tidak ditemukan"))?;
+
+    let entries = state.sitemap.load(&state.pool).await?;
+
+    let chunk = entries
+        .chunks(state.sitemap.max_urls)
+        .nth(page - 1)
+        .ok_or_else(|| AppError::not_found("Sitemap tidak ditemukan"))?;
+
+    Ok(xml_response(render_urlset(&state.frontend_url, chunk)))
+}
+
+fn render_urlset(base_url: &str, entries: &[SitemapEntry]) -> String {
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n");
+    for entry in entries {
+        xml.push_str("  <url>\n");
+        xml.push_str(&format!("    <loc>{}</loc>\n", escape(&format!("{base_url}{}", entry.path))));
+        if let Some(lastmod) = entry.lastmod {
+            xml.push_str(&format!("    <lastmod>{}</lastmod>\n", lastmod.to_rfc3339()));
+        }
+        xml.push_str("  </url>\n");
+    }
+    xml.push_str("</urlset>\n");
+    xml
+}
+
+fn xml_response(body: String) -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, "application/xml; charset=utf-8"),
+            (header::CACHE_CONTROL, CACHE_CONTROL),
+        ],
+        body,
+    )
+        .into_response()
+}
@@ -0,0 +1,198 @@
+use sqlx::{PgConnection, PgPool, Postgres, Transaction};
+
+use crate::error::AppResult;
+
+// Batas panjang slug supaya URL tetap enak dibaca
+const MAX_SLUG_LEN: usize = 80;
+// Berapa kali transaksi diulang kalau slug keburu dipakai request lain
+const MAX_ATTEMPTS: usize = 3;
+
+/// "Curug Cipendok (Baturraden)" -> "curug-cipendok-baturraden"
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    let mut slug = slug.trim_end_matches('-').to_string();
+    if slug.len() > MAX_SLUG_LEN {
+        slug.truncate(MAX_SLUG_LEN);
+        slug = slug.trim_end_matches('-').to_string();
+    }
+    if slug.is_empty() {
+        slug.push_str("item");
+    }
+    slug
+}
+
+// true kalau slug = base atau base-N (hasil suffix tabrakan)
+fn has_base(slug: &str, base: &str) -> bool {
+    slug == base
+        || slug
+            .strip_prefix(base)
+            .and_then(|rest| rest.strip_prefix('-'))
+            .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Cari slug yang belum dipakai di `table` (termasuk slug lama di slug_redirects).
+/// Tabrakan diberi suffix angka: nama, nama-2, nama-3, ...
+/// Jalankan di transaksi yang sama dengan INSERT/UPDATE-nya (lihat `finish`).
+pub async fn unique_slug(
+    conn: &mut PgConnection,
+    table: &'static str,
+    text: &str,
+    exclude_id: Option<i32>,
+) -> Result<String, sqlx::Error> {
+    let base = slugify(text);
+    let sql = format!(
+        "SELECT EXISTS(SELECT 1 FROM {table} WHERE slug = $1 AND ($2::int IS NULL OR id <> $2)) \
+         OR EXISTS(SELECT 1 FROM slug_redirects WHERE table_name = $3 AND old_slug = $1 \
+                   AND ($2::int IS NULL OR target_id <> $2))"
+    );
+
+    let mut candidate = base.clone();
+    let mut n = 2;
+    loop {
+        let taken: bool = sqlx::query_scalar(&sql)
+            .bind(&candidate)
+            .bind(exclude_id)
+            .bind(table)
+            .fetch_one(&mut *conn)
+            .await?;
+        if !taken {
+            return Ok(candidate);
+        }
+        candidate = format!("{base}-{n}");
+        n += 1;
+    }
+}
+
+/// Slug untuk data yang di-rename. Slug lama disimpan di slug_redirects supaya link lama tetap jalan.
+/// Return None kalau id tidak ada. Harus satu transaksi dengan UPDATE-nya: kalau UPDATE gagal (412, 404),
+/// redirect ikut batal.
+pub async fn slug_for_update(
+    conn: &mut PgConnection,
+    table: &'static str,
+    id: i32,
+    text: &str,
+) -> Result<Option<String>, sqlx::Error> {
+    let current: Option<String> = sqlx::query_scalar(&format!("SELECT slug FROM {table} WHERE id = $1"))
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await?;
+    let Some(current) = current else {
+        return Ok(None);
+    };
+
+    // Nama tidak berubah (secara slug) -> slug tetap stabil
+    if has_base(&current, &slugify(text)) {
+        return Ok(Some(current));
+    }
+
+    let new_slug = unique_slug(conn, table, text, Some(id)).await?;
+
+    sqlx::query(
+        "INSERT INTO slug_redirects (table_name, old_slug, target_id) VALUES ($1, $2, $3)
+         ON CONFLICT (table_name, old_slug) DO UPDATE SET target_id = EXCLUDED.target_id",
+    )
+    .bind(table)
+    .bind(&current)
+    .bind(id)
+    .execute(&mut *conn)
+    .await?;
+
+    // Kalau balik ke nama lama, slug itu bukan redirect lagi
+    sqlx::query("DELETE FROM slug_redirects WHERE table_name = $1 AND old_slug = $2")
+        .bind(table)
+        .bind(&new_slug)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(Some(new_slug))
+}
+
+/// Akhiri satu percobaan transaksi yang menulis slug (cari slug, redirect, INSERT/UPDATE): commit kalau berhasil.
+/// `Ok(None)` = slug yang dipilih keburu dipakai request lain (unique index, 23505), ulangi dengan transaksi baru.
+pub async fn finish<T>(tx: Transaction<'_, Postgres>, result: AppResult<T>, attempt: &mut usize) -> AppResult<Option<T>> {
+    match result {
+        Ok(value) => {
+            tx.commit().await?;
+            Ok(Some(value))
+        }
+        Err(err) if err.is_slug_conflict() && *attempt < MAX_ATTEMPTS => {
+            *attempt += 1;
+            Ok(None)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Slug lama -> slug sekarang (untuk redirect 308).
+pub async fn find_redirect(
+    pool: &PgPool,
+    table: &'static str,
+    old_slug: &str,
+) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar(&format!(
+        "SELECT t.slug FROM slug_redirects r JOIN {table} t ON t.id = r.target_id
+         WHERE r.table_name = $1 AND r.old_slug = $2 AND t.deleted_at IS NULL"
+    ))
+    .bind(table)
+    .bind(old_slug)
+    .fetch_optional(pool)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slugify_collapses_spaces_and_symbols() {
+        assert_eq!(slugify("Kopi  Kenangan!!"), "kopi-kenangan");
+        assert_eq!(slugify("Curug Cipendok (Baturraden)"), "curug-cipendok-baturraden");
+        assert_eq!(slugify("  --Pantai Baru-- "), "pantai-baru");
+    }
+
+    #[test]
+    fn slugify_non_ascii_becomes_separator() {
+        assert_eq!(slugify("Kafé Ñandu 2"), "kaf-andu-2");
+        assert_eq!(slugify("日本 Food"), "food");
+    }
+
+    #[test]
+    fn slugify_empty_or_symbols_only_falls_back() {
+        assert_eq!(slugify(""), "item");
+        assert_eq!(slugify("   "), "item");
+        assert_eq!(slugify("!!! ???"), "item");
+        assert_eq!(slugify("日本"), "item");
+    }
+
+    #[test]
+    fn slugify_truncates_without_trailing_dash() {
+        assert_eq!(slugify(&"a".repeat(100)), "a".repeat(MAX_SLUG_LEN));
+
+        // Potongan di posisi 80 jatuh tepat di '-'
+        let text = format!("{} bb", "a".repeat(MAX_SLUG_LEN - 1));
+        let slug = slugify(&text);
+        assert_eq!(slug, "a".repeat(MAX_SLUG_LEN - 1));
+        assert!(!slug.ends_with('-'));
+    }
+
+    #[test]
+    fn has_base_accepts_numeric_suffix_only() {
+        assert!(has_base("foo", "foo"));
+        assert!(has_base("foo-2", "foo"));
+        assert!(has_base("foo-12", "foo"));
+        assert!(!has_base("foo-bar", "foo"));
+        assert!(!has_base("foo-", "foo"));
+        assert!(!has_base("foo-2-3", "foo"));
+        assert!(!has_base("foobar", "foo"));
+        assert!(!has_base("foobar-2", "foo"));
+        assert!(!has_base("fo", "foo"));
+    }
+}
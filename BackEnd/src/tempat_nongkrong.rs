@@ -1,16 +1,28 @@
 use axum::{
-    extract::{Path, State},
+    extract::State,
     http::StatusCode,
+    response::{IntoResponse, Redirect, Response},
     Json,
 };
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use validator::Validate;
 
 use crate::app_state::AppState;
+use crate::error::{AppError, AppPath, AppResult};
+use crate::auth::AdminUser;
+use crate::etag::{self, IfMatch};
+use crate::revision::{self, Action};
+use crate::validation::{validate_image_url, validate_maps_url, validate_not_blank, validate_time, ValidJson};
+use crate::slug;
+
+const TABLE: &str = "tempat_nongkrong";
+const COLUMNS: &str = "id, slug, nama_tempat, kategori, alamat, jam_buka, jam_tutup, htm, link_gmaps, link_foto, version";
 
 #[derive(Debug, Serialize, FromRow)]
 pub struct TempatNongkrong {
     pub id: i32,
+    pub slug: String,
     pub nama_tempat: String,
     pub kategori: String,
     pub alamat: String,
@@ -19,121 +31,263 @@ pub struct TempatNongkrong {
     pub htm: i32,
     pub link_gmaps: String,
     pub link_foto: String,
+    pub version: i32,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Validate)]
 pub struct TempatNongkrongPayload {
+    #[validate(length(min = 1, max = 120, message = "nama_tempat harus 1-120 karakter"), custom(function = "validate_not_blank"))]
     pub nama_tempat: String,
+    #[validate(length(min = 1, max = 50, message = "kategori harus 1-50 karakter"), custom(function = "validate_not_blank"))]
     pub kategori: String,
+    #[validate(length(min = 1, max = 300, message = "alamat harus 1-300 karakter"), custom(function = "validate_not_blank"))]
     pub alamat: String,
+    #[validate(custom(function = "validate_time"))]
     pub jam_buka: String,
+    #[validate(custom(function = "validate_time"))]
     pub jam_tutup: String,
+    #[validate(range(min = 0, max = 10_000_000, message = "htm harus antara 0 dan 10000000"))]
     pub htm: i32,
+    #[validate(length(max = 500, message = "maksimal 500 karakter"), custom(function = "validate_maps_url"))]
     pub link_gmaps: String,
+    #[validate(length(max = 500, message = "maksimal 500 karakter"), custom(function = "validate_image_url"))]
     pub link_foto: String,
 }
 
+// Body PATCH: field yang tidak dikirim tidak diubah
+#[derive(Debug, Deserialize, Validate)]
+pub struct TempatNongkrongPatch {
+    #[validate(length(min = 1, max = 120, message = "nama_tempat harus 1-120 karakter"), custom(function = "validate_not_blank"))]
+    pub nama_tempat: Option<String>,
+    #[validate(length(min = 1, max = 50, message = "kategori harus 1-50 karakter"), custom(function = "validate_not_blank"))]
+    pub kategori: Option<String>,
+    #[validate(length(min = 1, max = 300, message = "alamat harus 1-300 karakter"), custom(function = "validate_not_blank"))]
+    pub alamat: Option<String>,
+    #[validate(custom(function = "validate_time"))]
+    pub jam_buka: Option<String>,
+    #[validate(custom(function = "validate_time"))]
+    pub jam_tutup: Option<String>,
+    #[validate(range(min = 0, max = 10_000_000, message = "htm harus antara 0 dan 10000000"))]
+    pub htm: Option<i32>,
+    #[validate(length(max = 500, message = "maksimal 500 karakter"), custom(function = "validate_maps_url"))]
+    pub link_gmaps: Option<String>,
+    #[validate(length(max = 500, message = "maksimal 500 karakter"), custom(function = "validate_image_url"))]
+    pub link_foto: Option<String>,
+}
+
 pub async fn get_tempat_nongkrong(
     State(state): State<AppState>,
-) -> Result<Json<Vec<TempatNongkrong>>, (StatusCode, String)> {
+) -> AppResult<Json<Vec<TempatNongkrong>>> {
     let rows = sqlx::query_as::<_, TempatNongkrong>(
-        r#"SELECT id, nama_tempat, kategori, alamat, jam_buka, jam_tutup, htm, link_gmaps, link_foto FROM tempat_nongkrong ORDER BY id"#,
+        &format!("SELECT {COLUMNS} FROM tempat_nongkrong WHERE deleted_at IS NULL ORDER BY id"),
     )
     .fetch_all(&state.pool)
-    .await
-    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("DB error: {e:?}")))?;
+    .await?;
 
     Ok(Json(rows))
 }
 
 pub async fn get_tempat_nongkrong_id(
     State(state): State<AppState>,
-    Path(id): Path<i32>,
-) -> Result<Json<TempatNongkrong>, (StatusCode, String)> {
+    AppPath(id): AppPath<i32>,
+) -> AppResult<Response> {
     let row = sqlx::query_as::<_, TempatNongkrong>(
-        r#"SELECT id, nama_tempat, kategori, alamat, jam_buka, jam_tutup, htm, link_gmaps, link_foto FROM tempat_nongkrong WHERE id = $1"#,
+        &format!("SELECT {COLUMNS} FROM tempat_nongkrong WHERE id = $1 AND deleted_at IS NULL"),
     )
     .bind(id)
     .fetch_one(&state.pool)
-    .await
-    .map_err(|e| (StatusCode::NOT_FOUND, format!("Not found / DB error: {e:?}")))?;
+    .await?;
 
-    Ok(Json(row))
+    Ok(etag::tagged(row.version, row))
 }
 
-pub async fn create_tempat_nongkrong(
+// GET /tempat_nongkrong/slug/{slug} -> slug lama di-redirect ke slug terbaru
+pub async fn get_tempat_nongkrong_by_slug(
     State(state): State<AppState>,
-    Json(payload): Json<TempatNongkrongPayload>,
-) -> Result<(StatusCode, Json<TempatNongkrong>), (StatusCode, String)> {
-    let inserted = sqlx::query_as::<_, TempatNongkrong>(
-        r#"
-        INSERT INTO tempat_nongkrong (nama_tempat, kategori, alamat, jam_buka, jam_tutup, htm, link_gmaps, link_foto)
-        VALUES ($1,$2,$3,$4,$5,$6,$7,$8)
-        RETURNING id, nama_tempat, kategori, alamat, jam_buka, jam_tutup, htm, link_gmaps, link_foto
-        "#,
+    AppPath(slug_param): AppPath<String>,
+) -> AppResult<Response> {
+    let row = sqlx::query_as::<_, TempatNongkrong>(
+        &format!("SELECT {COLUMNS} FROM tempat_nongkrong WHERE slug = $1 AND deleted_at IS NULL"),
     )
-    .bind(payload.nama_tempat)
-    .bind(payload.kategori)
-    .bind(payload.alamat)
-    .bind(payload.jam_buka)
-    .bind(payload.jam_tutup)
-    .bind(payload.htm)
-    .bind(payload.link_gmaps)
-    .bind(payload.link_foto)
-    .fetch_one(&state.pool)
-    .await
-    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("DB error: {e:?}")))?;
+    .bind(&slug_param)
+    .fetch_optional(&state.pool)
+    .await?;
+
+    if let Some(row) = row {
+        return Ok(etag::tagged(row.version, row));
+    }
+
+    match slug::find_redirect(&state.pool, TABLE, &slug_param).await? {
+        Some(current) => Ok(Redirect::permanent(&format!("/tempat_nongkrong/slug/{current}")).into_response()),
+        None => Err(AppError::not_found("Slug tidak ditemukan")),
+    }
+}
+
+pub async fn create_tempat_nongkrong(
+    State(state): State<AppState>,
+    admin: AdminUser,
+    ValidJson(payload): ValidJson<TempatNongkrongPayload>,
+) -> AppResult<(StatusCode, Json<TempatNongkrong>)> {
+    // Slug, INSERT dan revisi dalam satu transaksi, diulang kalau slug keburu dipakai request lain
+    let mut attempt = 1;
+    let inserted = loop {
+        let mut tx = state.pool.begin().await?;
+        let result = async {
+            let new_slug = slug::unique_slug(&mut tx, TABLE, &payload.nama_tempat, None).await?;
+
+            let inserted = sqlx::query_as::<_, TempatNongkrong>(&format!(
+                r#"
+                INSERT INTO tempat_nongkrong (nama_tempat, kategori, alamat, jam_buka, jam_tutup, htm, link_gmaps, link_foto, slug)
+                VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9)
+                RETURNING {COLUMNS}
+                "#,
+            ))
+            .bind(&payload.nama_tempat)
+            .bind(&payload.kategori)
+            .bind(&payload.alamat)
+            .bind(&payload.jam_buka)
+            .bind(&payload.jam_tutup)
+            .bind(payload.htm)
+            .bind(&payload.link_gmaps)
+            .bind(&payload.link_foto)
+            .bind(&new_slug)
+            .fetch_one(&mut *tx)
+            .await?;
 
+            revision::record(&mut tx, TABLE, inserted.id, Action::Create, &admin, None).await?;
+            Ok(inserted)
+        }
+        .await;
+        if let Some(inserted) = slug::finish(tx, result, &mut attempt).await? {
+            break inserted;
+        }
+    };
+
+    state.sitemap.invalidate();
     Ok((StatusCode::CREATED, Json(inserted)))
 }
 
 // --- BARU: UPDATE ---
+// PUT = payload lengkap. If-Match opsional, kalau dikirim harus sama dengan ETag terakhir.
 pub async fn update_tempat_nongkrong(
     State(state): State<AppState>,
-    Path(id): Path<i32>,
-    Json(payload): Json<TempatNongkrongPayload>,
-) -> Result<Json<String>, (StatusCode, String)> {
-    let result = sqlx::query(
-        r#"
-        UPDATE tempat_nongkrong 
-        SET nama_tempat=$1, kategori=$2, alamat=$3, jam_buka=$4, jam_tutup=$5, htm=$6, link_gmaps=$7, link_foto=$8 
-        WHERE id=$9
-        "#
-    )
-    .bind(payload.nama_tempat)
-    .bind(payload.kategori)
-    .bind(payload.alamat)
-    .bind(payload.jam_buka)
-    .bind(payload.jam_tutup)
-    .bind(payload.htm)
-    .bind(payload.link_gmaps)
-    .bind(payload.link_foto)
-    .bind(id)
-    .execute(&state.pool)
-    .await
-    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("DB Update Error: {e:?}")))?;
+    admin: AdminUser,
+    AppPath(id): AppPath<i32>,
+    IfMatch(expected): IfMatch,
+    ValidJson(payload): ValidJson<TempatNongkrongPayload>,
+) -> AppResult<Response> {
+    let updated = save_tempat_nongkrong(&state, &admin, id, payload, expected).await?;
+    Ok(etag::tagged(updated.version, updated))
+}
 
-    if result.rows_affected() == 0 {
-        return Err((StatusCode::NOT_FOUND, "ID tidak ditemukan".to_string()));
-    }
+// PATCH = hanya field yang berubah, sisanya diambil dari data sekarang.
+// Tanpa If-Match tetap dikunci ke versi yang dibaca, supaya perubahan admin lain di antaranya tidak tertimpa
+pub async fn patch_tempat_nongkrong(
+    State(state): State<AppState>,
+    admin: AdminUser,
+    AppPath(id): AppPath<i32>,
+    IfMatch(expected): IfMatch,
+    ValidJson(patch): ValidJson<TempatNongkrongPatch>,
+) -> AppResult<Response> {
+    let current = sqlx::query_as::<_, TempatNongkrong>(&format!("SELECT {COLUMNS} FROM tempat_nongkrong WHERE id = $1 AND deleted_at IS NULL"))
+        .bind(id)
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or_else(|| AppError::not_found("ID tidak ditemukan"))?;
+
+    let payload = TempatNongkrongPayload {
+        nama_tempat: patch.nama_tempat.unwrap_or(current.nama_tempat),
+        kategori: patch.kategori.unwrap_or(current.kategori),
+        alamat: patch.alamat.unwrap_or(current.alamat),
+        jam_buka: patch.jam_buka.unwrap_or(current.jam_buka),
+        jam_tutup: patch.jam_tutup.unwrap_or(current.jam_tutup),
+        htm: patch.htm.unwrap_or(current.htm),
+        link_gmaps: patch.link_gmaps.unwrap_or(current.link_gmaps),
+        link_foto: patch.link_foto.unwrap_or(current.link_foto),
+    };
+
+    let updated = save_tempat_nongkrong(&state, &admin, id, payload, expected.or(Some(current.version))).await?;
+    Ok(etag::tagged(updated.version, updated))
+}
+
+async fn save_tempat_nongkrong(
+    state: &AppState,
+    admin: &AdminUser,
+    id: i32,
+    payload: TempatNongkrongPayload,
+    expected: Option<i32>,
+) -> AppResult<TempatNongkrong> {
+    // Rename -> slug baru, slug lama tetap jalan lewat redirect. Cek versi, slug, redirect, UPDATE dan revisi
+    // satu transaksi: kalau UPDATE gagal (412 / 404) redirect dan revisi ikut batal
+    let mut attempt = 1;
+    let updated = loop {
+        let mut tx = state.pool.begin().await?;
+        let result = async {
+            let before = revision::snapshot(&mut tx, TABLE, id).await?;
+            etag::check(&mut tx, TABLE, id, expected).await?;
+
+            let new_slug = slug::slug_for_update(&mut tx, TABLE, id, &payload.nama_tempat)
+                .await?
+                .ok_or_else(|| AppError::not_found("ID tidak ditemukan"))?;
+
+            let updated = sqlx::query_as::<_, TempatNongkrong>(&format!(
+                r#"
+                UPDATE tempat_nongkrong 
+                SET nama_tempat=$1, kategori=$2, alamat=$3, jam_buka=$4, jam_tutup=$5, htm=$6, link_gmaps=$7, link_foto=$8, slug=$10, updated_at=now(), version=version+1 
+                WHERE id=$9 AND deleted_at IS NULL AND ($11::int IS NULL OR version=$11)
+                RETURNING {COLUMNS}
+                "#
+            ))
+            .bind(&payload.nama_tempat)
+            .bind(&payload.kategori)
+            .bind(&payload.alamat)
+            .bind(&payload.jam_buka)
+            .bind(&payload.jam_tutup)
+            .bind(payload.htm)
+            .bind(&payload.link_gmaps)
+            .bind(&payload.link_foto)
+            .bind(id)
+            .bind(&new_slug)
+            .bind(expected)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+            let Some(updated) = updated else {
+                return Err(etag::missing_or_stale(&mut tx, TABLE, id).await);
+            };
+            revision::record(&mut tx, TABLE, id, Action::Update, admin, before).await?;
+            Ok(updated)
+        }
+        .await;
+        if let Some(updated) = slug::finish(tx, result, &mut attempt).await? {
+            break updated;
+        }
+    };
 
-    Ok(Json("Update Berhasil".to_string()))
+    state.sitemap.invalidate();
+    Ok(updated)
 }
 
 // --- BARU: DELETE ---
 pub async fn delete_tempat_nongkrong(
     State(state): State<AppState>,
-    Path(id): Path<i32>,
-) -> Result<Json<String>, (StatusCode, String)> {
-    let result = sqlx::query("DELETE FROM tempat_nongkrong WHERE id = $1")
+    admin: AdminUser,
+    AppPath(id): AppPath<i32>,
+) -> AppResult<StatusCode> {
+    // Snapshot, soft delete dan revisi satu transaksi
+    let mut tx = state.pool.begin().await?;
+    let before = revision::snapshot(&mut tx, TABLE, id).await?;
+    let result = sqlx::query("UPDATE tempat_nongkrong SET deleted_at = now() WHERE id = $1 AND deleted_at IS NULL")
         .bind(id)
-        .execute(&state.pool)
-        .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("DB Delete Error: {e:?}")))?;
+        .execute(&mut *tx)
+        .await?;
 
     if result.rows_affected() == 0 {
-        return Err((StatusCode::NOT_FOUND, "ID tidak ditemukan".to_string()));
+        return Err(AppError::not_found("ID tidak ditemukan"));
     }
 
-    Ok(Json("Delete Berhasil".to_string()))
+    revision::record(&mut tx, TABLE, id, Action::Delete, &admin, before).await?;
+    tx.commit().await?;
+    state.sitemap.invalidate();
+    Ok(StatusCode::NO_CONTENT)
 }
\ No newline at end of file
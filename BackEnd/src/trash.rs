@@ -0,0 +1,177 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::{FromRow, PgPool};
+use std::time::Duration;
+
+use crate::app_state::AppState;
+use crate::audit::{self, AuditEvent};
+use crate::auth::AdminUser;
+use crate::client_info::ClientInfo;
+use crate::content::{self, find_table};
+use crate::error::{AppError, AppPath, AppQuery, AppResult};
+use crate::revision::{self, Action};
+
+pub const DEFAULT_RETENTION_DAYS: i32 = 30;
+// Purge dicek tiap jam, cukup teliti untuk retensi dalam hitungan hari
+const PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct TrashItem {
+    pub table_name: String,
+    pub id: i32,
+    pub slug: String,
+    pub title: String,
+    pub deleted_at: DateTime<Utc>,
+    pub purge_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TrashQuery {
+    pub table: Option<String>,
+}
+
+// GET /api/trash?table=kuliner -> data yang dihapus, terbaru dulu
+pub async fn list_trash(
+    State(state): State<AppState>,
+    _admin: AdminUser,
+    AppQuery(query): AppQuery<TrashQuery>,
+) -> AppResult<Json<Vec<TrashItem>>> {
+    let tables = match query.table.as_deref() {
+        Some(name) => vec![find_table(name)?],
+        None => content::TABLES.to_vec(),
+    };
+
+    let mut items = Vec::new();
+    for (table, title) in tables {
+        let rows = sqlx::query_as::<_, TrashItem>(&format!(
+            "SELECT $1::text AS table_name, id, slug, {title} AS title, deleted_at,
+                    deleted_at + make_interval(days => $2) AS purge_at
+             FROM {table} WHERE deleted_at IS NOT NULL"
+        ))
+        .bind(table)
+        .bind(state.trash_retention_days)
+        .fetch_all(&state.pool)
+        .await?;
+        items.extend(rows);
+    }
+
+    items.sort_by_key(|item| std::cmp::Reverse(item.deleted_at));
+    Ok(Json(items))
+}
+
+// POST /api/trash/{table}/{id}/restore
+pub async fn restore_from_trash(
+    State(state): State<AppState>,
+    admin: AdminUser,
+    AppPath((table, id)): AppPath<(String, i32)>,
+) -> AppResult<StatusCode> {
+    let (table, _) = find_table(&table)?;
+    // Snapshot, restore dan revisi satu transaksi
+    let mut tx = state.pool.begin().await?;
+    let before = revision::snapshot(&mut tx, table, id).await?;
+
+    let result = sqlx::query(&format!(
+        "UPDATE {table} SET deleted_at = NULL, updated_at = now() WHERE id = $1 AND deleted_at IS NOT NULL"
+    ))
+    .bind(id)
+    .execute(&mut *tx)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found("Data tidak ada di tempat sampah"));
+    }
+
+    revision::record(&mut tx, table, id, Action::Restore, &admin, before).await?;
+    tx.commit().await?;
+    state.sitemap.invalidate();
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// DELETE /api/trash/{table}/{id} -> hapus permanen tanpa menunggu purge
+pub async fn purge_from_trash(
+    State(state): State<AppState>,
+    admin: AdminUser,
+    client: ClientInfo,
+    AppPath((table, id)): AppPath<(String, i32)>,
+) -> AppResult<StatusCode> {
+    let (table, _) = find_table(&table)?;
+
+    let ids: Vec<i32> = sqlx::query_scalar(&format!(
+        "DELETE FROM {table} WHERE id = $1 AND deleted_at IS NOT NULL RETURNING id"
+    ))
+    .bind(id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    if ids.is_empty() {
+        return Err(AppError::not_found("Data tidak ada di tempat sampah"));
+    }
+
+    delete_redirects(&state.pool, table, &ids).await?;
+
+    let event = AuditEvent::success("trash.purge")
+        .actor(Some(admin.id), &admin.username)
+        .details(json!({ "table": table, "id": id }));
+    audit::record(&state.pool, &client, event).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Hapus permanen semua data yang sudah lebih lama dari `retention_days` di tempat sampah.
+pub async fn purge_expired(pool: &PgPool, retention_days: i32) -> Result<u64, sqlx::Error> {
+    let mut total = 0;
+    for (table, _) in content::TABLES {
+        let ids: Vec<i32> = sqlx::query_scalar(&format!(
+            "DELETE FROM {table} WHERE deleted_at < now() - make_interval(days => $1) RETURNING id"
+        ))
+        .bind(retention_days)
+        .fetch_all(pool)
+        .await?;
+
+        delete_redirects(pool, table, &ids).await?;
+        total += ids.len() as u64;
+    }
+    Ok(total)
+}
+
+// Slug lama milik data yang sudah dihapus permanen tidak perlu disimpan lagi
+async fn delete_redirects(pool: &PgPool, table: &str, ids: &[i32]) -> Result<(), sqlx::Error> {
+    if ids.is_empty() {
+        return Ok(());
+    }
+    sqlx::query("DELETE FROM slug_redirects WHERE table_name = $1 AND target_id = ANY($2)")
+        .bind(table)
+        .bind(ids)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Jalankan purge di background: sekali saat start, lalu tiap PURGE_INTERVAL.
+pub fn spawn_purge_task(pool: PgPool, retention_days: i32) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PURGE_INTERVAL);
+        loop {
+            interval.tick().await;
+            match purge_expired(&pool, retention_days).await {
+                Ok(0) => {}
+                Ok(n) => {
+                    tracing::info!("Purged {} rows from trash", n);
+                    // Operasi massal oleh sistem, tidak ada IP / user agent
+                    let event = AuditEvent::success("trash.purge_expired")
+                        .actor(None, "system")
+                        .details(json!({ "rows": n, "retention_days": retention_days }));
+                    if let Err(e) = audit::record(&pool, &ClientInfo::default(), event).await {
+                        tracing::error!("Failed to write audit log: {:?}", e);
+                    }
+                }
+                Err(e) => tracing::error!("Failed to purge trash: {:?}", e),
+            }
+        }
+    });
+}
@@ -0,0 +1,438 @@
+use axum::{extract::State, http::StatusCode, Json};
+use chrono::{DateTime, Utc};
+use jsonwebtoken::Validation;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use sqlx::{FromRow, PgConnection, PgPool};
+use totp_rs::{Algorithm, Secret, TOTP};
+
+use crate::app_state::AppState;
+use crate::audit::{self, AuditEvent};
+use crate::auth::{AdminSession, AdminUser, JwtKeys, TokenResponse, ADMIN_ROLE};
+use crate::client_info::ClientInfo;
+use crate::error::{AppError, AppJson, AppPath, AppResult};
+use crate::login_guard;
+use crate::session;
+
+// Nama yang tampil di aplikasi authenticator (Google Authenticator, Authy, ...)
+const ISSUER: &str = "ExploreMas";
+const STEP_SECS: u64 = 30;
+const RECOVERY_CODE_COUNT: usize = 10;
+// Token sementara antara password benar dan kode 2FA
+const CHALLENGE_TTL_SECS: i64 = 5 * 60;
+const CHALLENGE_AUDIENCE: &str = "admin_2fa";
+
+#[derive(FromRow)]
+struct TwoFactorRow {
+    id: i32,
+    username: String,
+    totp_secret: Option<String>,
+    totp_enabled_at: Option<DateTime<Utc>>,
+    is_super_admin: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ChallengeClaims {
+    sub: i32,
+    aud: String,
+    username: String,
+    iat: i64,
+    exp: i64,
+}
+
+/// Jawaban /admin_login kalau password benar tapi admin punya 2FA.
+#[derive(Serialize)]
+pub struct MfaChallenge {
+    pub mfa_required: bool,
+    pub mfa_token: String,
+    pub expires_in: i64,
+}
+
+#[derive(Deserialize)]
+pub struct CodeRequest {
+    pub code: String,
+}
+
+#[derive(Deserialize)]
+pub struct VerifyLoginRequest {
+    pub mfa_token: String,
+    pub code: String,
+}
+
+#[derive(Serialize)]
+pub struct TwoFactorStatus {
+    pub enabled: bool,
+    pub required: bool,
+    pub recovery_codes_remaining: i64,
+}
+
+#[derive(Serialize)]
+pub struct SetupResponse {
+    pub secret: String,
+    // Dijadikan QR code oleh frontend, atau secret diketik manual di aplikasi authenticator
+    pub otpauth_uri: String,
+}
+
+#[derive(Serialize)]
+pub struct EnableResponse {
+    // Hanya ditampilkan sekali, di database cuma disimpan hash-nya
+    pub recovery_codes: Vec<String>,
+    // Token baru yang sudah ditandai lewat 2FA
+    #[serde(flatten)]
+    pub token: TokenResponse,
+}
+
+#[derive(Serialize)]
+pub struct RecoveryCodesResponse {
+    pub recovery_codes: Vec<String>,
+}
+
+async fn load(pool: &PgPool, id: i32) -> AppResult<TwoFactorRow> {
+    sqlx::query_as::<_, TwoFactorRow>(
+        "SELECT id, username, totp_secret, totp_enabled_at, is_super_admin FROM accounts WHERE id = $1 AND role = 'admin'",
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::not_found("Admin tidak ditemukan"))
+}
+
+pub async fn is_enabled(pool: &PgPool, admin_id: i32) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar("SELECT totp_enabled_at IS NOT NULL FROM accounts WHERE id = $1 AND role = 'admin'")
+        .bind(admin_id)
+        .fetch_one(pool)
+        .await
+}
+
+fn totp(secret: &str, username: &str) -> AppResult<TOTP> {
+    let bytes = Secret::Encoded(secret.to_string())
+        .to_bytes()
+        .map_err(|e| AppError::Internal(format!("Invalid TOTP secret: {e:?}")))?;
+    // ':' tidak boleh ada di label otpauth
+    TOTP::new(Algorithm::SHA1, 6, 0, STEP_SECS, bytes, Some(ISSUER.to_string()), username.replace(':', "_"))
+        .map_err(|e| AppError::Internal(format!("Invalid TOTP config: {e:?}")))
+}
+
+fn new_secret() -> String {
+    match Secret::generate_secret().to_encoded() {
+        Secret::Encoded(s) => s,
+        Secret::Raw(_) => unreachable!("to_encoded always returns Secret::Encoded"),
+    }
+}
+
+// Kode 6 digit dari authenticator. Toleransi satu langkah (30 detik) sebelum/sesudah untuk jam yang meleset.
+// Langkah yang sudah pernah dipakai ditolak, jadi kode yang sama tidak bisa dipakai ulang.
+async fn check_totp(pool: &PgPool, admin: &TwoFactorRow, secret: &str, code: &str) -> AppResult<bool> {
+    if code.len() != 6 || !code.bytes().all(|b| b.is_ascii_digit()) {
+        return Ok(false);
+    }
+    let totp = totp(secret, &admin.username)?;
+    let current = Utc::now().timestamp() as u64 / STEP_SECS;
+
+    let Some(step) = [current - 1, current, current + 1]
+        .into_iter()
+        .find(|step| totp.check(code, step * STEP_SECS))
+    else {
+        return Ok(false);
+    };
+
+    let result = sqlx::query(
+        "UPDATE accounts SET totp_last_step = $2 WHERE id = $1 AND (totp_last_step IS NULL OR totp_last_step < $2)",
+    )
+    .bind(admin.id)
+    .bind(step as i64)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() == 1)
+}
+
+fn normalize_recovery_code(code: &str) -> String {
+    code.chars().filter(char::is_ascii_alphanumeric).collect::<String>().to_uppercase()
+}
+
+fn hash_recovery_code(code: &str) -> String {
+    format!("{:x}", Sha256::digest(normalize_recovery_code(code).as_bytes()))
+}
+
+async fn use_recovery_code(pool: &PgPool, admin_id: i32, code: &str) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        "UPDATE admin_recovery_codes SET used_at = now()
+         WHERE id = (SELECT id FROM admin_recovery_codes
+                     WHERE admin_id = $1 AND code_hash = $2 AND used_at IS NULL LIMIT 1)",
+    )
+    .bind(admin_id)
+    .bind(hash_recovery_code(code))
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() == 1)
+}
+
+/// Cek kode TOTP atau recovery code. Mengembalikan cara yang dipakai, None kalau salah.
+async fn verify_code(pool: &PgPool, admin: &TwoFactorRow, code: &str) -> AppResult<Option<&'static str>> {
+    let Some(secret) = admin.totp_secret.as_deref().filter(|_| admin.totp_enabled_at.is_some()) else {
+        return Ok(None);
+    };
+    let code = code.trim().replace(' ', "");
+    if check_totp(pool, admin, secret, &code).await? {
+        return Ok(Some("totp"));
+    }
+    if use_recovery_code(pool, admin.id, &code).await? {
+        return Ok(Some("recovery_code"));
+    }
+    Ok(None)
+}
+
+// Format XXXXX-XXXXX (base32), 50 bit acak per kode
+async fn replace_recovery_codes(conn: &mut PgConnection, admin_id: i32) -> Result<Vec<String>, sqlx::Error> {
+    let codes: Vec<String> = (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let raw = new_secret();
+            format!("{}-{}", &raw[..5], &raw[5..10])
+        })
+        .collect();
+    let hashes: Vec<String> = codes.iter().map(|c| hash_recovery_code(c)).collect();
+
+    sqlx::query("DELETE FROM admin_recovery_codes WHERE admin_id = $1")
+        .bind(admin_id)
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query("INSERT INTO admin_recovery_codes (admin_id, code_hash) SELECT $1, UNNEST($2::text[])")
+        .bind(admin_id)
+        .bind(&hashes)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(codes)
+}
+
+async fn clear(conn: &mut PgConnection, admin_id: i32) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE accounts SET totp_secret = NULL, totp_enabled_at = NULL, totp_last_step = NULL WHERE id = $1")
+        .bind(admin_id)
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query("DELETE FROM admin_recovery_codes WHERE admin_id = $1")
+        .bind(admin_id)
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+/// Token sementara untuk langkah kedua login. Bukan token admin: tidak bisa dipakai di endpoint lain.
+pub fn issue_challenge(keys: &JwtKeys, id: i32, username: &str) -> AppResult<MfaChallenge> {
+    let now = Utc::now().timestamp();
+    let mfa_token = keys.sign(&ChallengeClaims {
+        sub: id,
+        aud: CHALLENGE_AUDIENCE.to_string(),
+        username: username.to_string(),
+        iat: now,
+        exp: now + CHALLENGE_TTL_SECS,
+    })?;
+    Ok(MfaChallenge { mfa_required: true, mfa_token, expires_in: CHALLENGE_TTL_SECS })
+}
+
+// POST /admin_login/verify { "mfa_token": "...", "code": "123456" } -> token admin
+// code boleh kode 6 digit dari authenticator atau salah satu recovery code
+pub async fn verify_login(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    AppJson(payload): AppJson<VerifyLoginRequest>,
+) -> AppResult<Json<TokenResponse>> {
+    let mut validation = Validation::default();
+    validation.set_audience(&[CHALLENGE_AUDIENCE]);
+    let claims: ChallengeClaims = state
+        .jwt
+        .verify(&payload.mfa_token, &validation)
+        .ok_or_else(|| AppError::Unauthorized("Sesi login 2FA tidak valid atau sudah kedaluwarsa, login ulang".to_string()))?;
+
+    // Tebakan kode 2FA dibatasi sama seperti tebakan password
+    let ip = client.ip.as_deref();
+    login_guard::begin_attempt(&state.pool, CHALLENGE_AUDIENCE, &claims.username, ip).await?;
+
+    let admin = load(&state.pool, claims.sub).await?;
+    let Some(method) = verify_code(&state.pool, &admin, &payload.code).await? else {
+        let event = AuditEvent::failure("admin.login_2fa").actor(Some(admin.id), &admin.username);
+        audit::record(&state.pool, &client, event).await?;
+        return Err(AppError::Unauthorized("Kode 2FA salah".to_string()));
+    };
+
+    login_guard::attempt_succeeded(&state.pool, CHALLENGE_AUDIENCE, &admin.username, ip).await?;
+    let event = AuditEvent::success("admin.login_2fa")
+        .actor(Some(admin.id), &admin.username)
+        .details(json!({ "method": method }));
+    audit::record(&state.pool, &client, event).await?;
+
+    Ok(Json(session::start(&state, &client, ADMIN_ROLE, admin.id, &admin.username, true).await?))
+}
+
+// GET /api/admin/2fa -> status 2FA admin yang login
+pub async fn get_status(
+    State(state): State<AppState>,
+    AdminSession(admin): AdminSession,
+) -> AppResult<Json<TwoFactorStatus>> {
+    let row = load(&state.pool, admin.id).await?;
+    let remaining: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM admin_recovery_codes WHERE admin_id = $1 AND used_at IS NULL")
+            .bind(admin.id)
+            .fetch_one(&state.pool)
+            .await?;
+
+    Ok(Json(TwoFactorStatus {
+        enabled: row.totp_enabled_at.is_some(),
+        required: state.admin_2fa_required,
+        recovery_codes_remaining: remaining,
+    }))
+}
+
+// POST /api/admin/2fa/setup -> secret baru + otpauth:// URI. 2FA baru aktif setelah /enable
+pub async fn setup(
+    State(state): State<AppState>,
+    AdminSession(admin): AdminSession,
+) -> AppResult<Json<SetupResponse>> {
+    let row = load(&state.pool, admin.id).await?;
+    if row.totp_enabled_at.is_some() {
+        return Err(AppError::Conflict("2FA sudah aktif".to_string()));
+    }
+
+    let secret = new_secret();
+    let otpauth_uri = totp(&secret, &row.username)?.get_url();
+
+    sqlx::query("UPDATE accounts SET totp_secret = $1, totp_last_step = NULL WHERE id = $2")
+        .bind(&secret)
+        .bind(admin.id)
+        .execute(&state.pool)
+        .await?;
+
+    Ok(Json(SetupResponse { secret, otpauth_uri }))
+}
+
+// POST /api/admin/2fa/enable { "code": "123456" } -> aktifkan 2FA, dapat recovery codes + token baru
+pub async fn enable(
+    State(state): State<AppState>,
+    AdminSession(admin): AdminSession,
+    client: ClientInfo,
+    AppJson(payload): AppJson<CodeRequest>,
+) -> AppResult<Json<EnableResponse>> {
+    let row = load(&state.pool, admin.id).await?;
+    if row.totp_enabled_at.is_some() {
+        return Err(AppError::Conflict("2FA sudah aktif".to_string()));
+    }
+    let secret = row
+        .totp_secret
+        .as_deref()
+        .ok_or_else(|| AppError::Conflict("Jalankan /api/admin/2fa/setup terlebih dahulu".to_string()))?;
+
+    // Tebakan kode di sini ikut hitungan yang sama dengan /admin_login/verify
+    let ip = client.ip.as_deref();
+    login_guard::begin_attempt(&state.pool, CHALLENGE_AUDIENCE, &admin.username, ip).await?;
+    if !check_totp(&state.pool, &row, secret, payload.code.trim()).await? {
+        return Err(AppError::Unauthorized("Kode 2FA salah".to_string()));
+    }
+
+    // 2FA aktif, recovery codes dan sesi baru: semua atau tidak sama sekali
+    let mut tx = state.pool.begin().await?;
+    sqlx::query("UPDATE accounts SET totp_enabled_at = now() WHERE id = $1")
+        .bind(admin.id)
+        .execute(&mut *tx)
+        .await?;
+    let recovery_codes = replace_recovery_codes(&mut tx, admin.id).await?;
+
+    let event = AuditEvent::success("admin.2fa_enabled").actor(Some(admin.id), &admin.username);
+    audit::record(&mut *tx, &client, event).await?;
+
+    // Sesi lama (tanpa 2FA) diganti sesi baru yang sudah lewat 2FA
+    let token = session::start_in(&mut tx, &state, &client, ADMIN_ROLE, admin.id, &admin.username, true).await?;
+    session::revoke(&mut *tx, &admin.session_id, "mfa_changed").await?;
+    tx.commit().await?;
+
+    login_guard::attempt_succeeded(&state.pool, CHALLENGE_AUDIENCE, &admin.username, ip).await?;
+    Ok(Json(EnableResponse { recovery_codes, token }))
+}
+
+// POST /api/admin/2fa/recovery_codes { "code": "123456" } -> recovery codes baru, yang lama tidak berlaku
+pub async fn regenerate_recovery_codes(
+    State(state): State<AppState>,
+    admin: AdminUser,
+    client: ClientInfo,
+    AppJson(payload): AppJson<CodeRequest>,
+) -> AppResult<Json<RecoveryCodesResponse>> {
+    let row = load(&state.pool, admin.id).await?;
+    if row.totp_enabled_at.is_none() {
+        return Err(AppError::Conflict("2FA belum aktif".to_string()));
+    }
+    let ip = client.ip.as_deref();
+    login_guard::begin_attempt(&state.pool, CHALLENGE_AUDIENCE, &admin.username, ip).await?;
+    if verify_code(&state.pool, &row, &payload.code).await?.is_none() {
+        return Err(AppError::Unauthorized("Kode 2FA salah".to_string()));
+    }
+
+    let mut tx = state.pool.begin().await?;
+    let recovery_codes = replace_recovery_codes(&mut tx, admin.id).await?;
+
+    let event = AuditEvent::success("admin.2fa_recovery_codes").actor(Some(admin.id), &admin.username);
+    audit::record(&mut *tx, &client, event).await?;
+    tx.commit().await?;
+
+    login_guard::attempt_succeeded(&state.pool, CHALLENGE_AUDIENCE, &admin.username, ip).await?;
+    Ok(Json(RecoveryCodesResponse { recovery_codes }))
+}
+
+// POST /api/admin/2fa/disable { "code": "123456" } -> matikan 2FA sendiri (tidak bisa kalau 2FA wajib)
+pub async fn disable(
+    State(state): State<AppState>,
+    admin: AdminUser,
+    client: ClientInfo,
+    AppJson(payload): AppJson<CodeRequest>,
+) -> AppResult<Json<TokenResponse>> {
+    if state.admin_2fa_required {
+        return Err(AppError::Forbidden("2FA wajib untuk semua admin".to_string()));
+    }
+    let row = load(&state.pool, admin.id).await?;
+    if row.totp_enabled_at.is_none() {
+        return Err(AppError::Conflict("2FA belum aktif".to_string()));
+    }
+    let ip = client.ip.as_deref();
+    login_guard::begin_attempt(&state.pool, CHALLENGE_AUDIENCE, &admin.username, ip).await?;
+    if verify_code(&state.pool, &row, &payload.code).await?.is_none() {
+        return Err(AppError::Unauthorized("Kode 2FA salah".to_string()));
+    }
+
+    let mut tx = state.pool.begin().await?;
+    clear(&mut tx, admin.id).await?;
+
+    let event = AuditEvent::success("admin.2fa_disabled").actor(Some(admin.id), &admin.username);
+    audit::record(&mut *tx, &client, event).await?;
+
+    let token = session::start_in(&mut tx, &state, &client, ADMIN_ROLE, admin.id, &admin.username, false).await?;
+    session::revoke(&mut *tx, &admin.session_id, "mfa_changed").await?;
+    tx.commit().await?;
+
+    login_guard::attempt_succeeded(&state.pool, CHALLENGE_AUDIENCE, &admin.username, ip).await?;
+    Ok(Json(token))
+}
+
+// POST /api/admin/{id}/2fa/reset -> super admin menghapus 2FA admin lain (HP hilang + recovery code habis).
+// Semua sesi admin itu ikut dicabut, dia harus login ulang dan memasang 2FA lagi
+pub async fn reset_for_admin(
+    State(state): State<AppState>,
+    admin: AdminUser,
+    client: ClientInfo,
+    AppPath(target_id): AppPath<i32>,
+) -> AppResult<StatusCode> {
+    if !load(&state.pool, admin.id).await?.is_super_admin {
+        return Err(AppError::Forbidden("Hanya super admin yang bisa reset 2FA".to_string()));
+    }
+    let target = load(&state.pool, target_id).await?;
+
+    let mut tx = state.pool.begin().await?;
+    clear(&mut tx, target.id).await?;
+    session::revoke_all(&mut *tx, ADMIN_ROLE, target.id, None, "mfa_reset").await?;
+
+    let event = AuditEvent::success("admin.2fa_reset")
+        .actor(Some(admin.id), &admin.username)
+        .details(json!({ "target_admin_id": target.id, "target_admin": target.username }));
+    audit::record(&mut *tx, &client, event).await?;
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -1,13 +1,23 @@
+use crate::account_token::{self, Purpose};
 use crate::app_state::AppState;
+use crate::audit::{self, AuditEvent};
+use crate::account::{Account, ACCOUNT_COLUMNS};
+use crate::auth::USER_ROLE;
+use crate::client_info::ClientInfo;
+use crate::error::{AppJson, AppResult};
+use crate::login_guard::{self, LOGIN_SCOPE};
+use crate::mailer::{self, Email};
+use crate::password;
+use crate::session;
+use crate::validation::ValidJson;
 use axum::{
     debug_handler,
     extract::State,
     http::StatusCode,
-    response::{IntoResponse, Json},
+    response::Json,
 };
-use bcrypt::{DEFAULT_COST, hash, verify};
 use serde::{Deserialize, Serialize};
-use sqlx::FromRow;
+use serde_json::json;
 use validator::Validate;
 
 #[derive(Serialize)]
@@ -17,92 +27,205 @@ pub struct UserResponse {
 
 #[derive(Deserialize, Validate)]
 pub struct RegisterRequest {
-    #[validate(length(min = 4, max = 16, message = "username min 4 and max 16 characters"))]
+    #[validate(length(min = 4, max = 16, message = "username harus 4-16 karakter"))]
     pub username: String,
-    #[validate(length(min = 8, max = 16, message = "password min 8 and max 16 characters"))]
+    // Panjang dan daftar password umum dicek dengan state.password_policy
     pub password: String,
-    #[validate(email)]
+    #[validate(email(message = "format email tidak valid"))]
     pub email: String,
 }
 
+#[derive(Deserialize, Validate)]
+pub struct EmailRequest {
+    #[validate(email(message = "format email tidak valid"))]
+    pub email: String,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct ResetPasswordRequest {
+    pub token: String,
+    pub new_password: String,
+}
+
 #[derive(Deserialize)]
-pub struct LoginRequest {
-    pub username: String,
-    pub password: String,
+pub struct TokenRequest {
+    pub token: String,
 }
 
-#[derive(FromRow)]
-pub struct UserSql {
-    pub id: i32,
-    pub username: String,
-    pub password: String,
-    pub email: String,
+// Kirim link verifikasi email (token baru, token verifikasi lama tidak berlaku lagi)
+async fn send_verification_email(state: &AppState, user_id: i32, email: &str) -> AppResult<()> {
+    let token = account_token::issue(&state.pool, &state.jwt, user_id, email, Purpose::VerifyEmail).await?;
+    let link = format!("{}/verify-email?token={}", state.frontend_url, token);
+    mailer::send_in_background(
+        state.mailer.clone(),
+        Email {
+            to: email.to_string(),
+            subject: "Verifikasi email akun ExploreMas".to_string(),
+            body: format!(
+                "Halo,\n\nKlik link berikut untuk memverifikasi email akun ExploreMas kamu (berlaku 48 jam):\n{link}\n\nAbaikan email ini kalau kamu tidak merasa mendaftar."
+            ),
+        },
+    );
+    Ok(())
 }
 
 #[debug_handler]
 pub async fn register_user(
     State(state): State<AppState>,
-    Json(payload): Json<RegisterRequest>,
-) -> impl IntoResponse {
-    if let Err(err) = payload.validate() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(UserResponse {
-                message: format!("{}", err),
-            }),
-        );
-    }
-
-    let hashed = hash(&payload.password, DEFAULT_COST).unwrap();
+    client: ClientInfo,
+    ValidJson(payload): ValidJson<RegisterRequest>,
+) -> AppResult<(StatusCode, Json<UserResponse>)> {
+    state.password_policy.check("password", &payload.password)?;
+    let hashed = password::hash(&payload.password)?;
 
-    let result = sqlx::query(
-        "INSERT INTO users(username, password, email) VALUES ($1, $2, $3)"
+    let new_id: i32 = sqlx::query_scalar(
+        "INSERT INTO accounts (role, username, password, email) VALUES ('user', $1, $2, $3) RETURNING id"
     )
         .bind(&payload.username)
         .bind(&hashed)
         .bind(&payload.email)
-        .execute(&state.pool)
-        .await;
-
-    match result {
-        Ok(_) => (
-            StatusCode::OK,
-            Json(UserResponse {
-                message: "Successfully registered user".to_string(),
-            }),
-        ),
-
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(UserResponse {
-                message: format!("Failed to register user: {}", e),
-            }),
-        ),
+        .fetch_one(&state.pool)
+        .await?;
+
+    let event = AuditEvent::success("user.register").actor(Some(new_id), &payload.username);
+    audit::record(&state.pool, &client, event).await?;
+
+    send_verification_email(&state, new_id, &payload.email).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(UserResponse {
+            message: "Registrasi berhasil".to_string(),
+        }),
+    ))
+}
+
+const FORGOT_PASSWORD_MESSAGE: &str = "Jika email terdaftar, link reset password sudah dikirim";
+
+// POST /forgot_password { "email": "..." }
+// Jawaban selalu sama (202), supaya tidak bisa dipakai untuk mengecek email mana yang terdaftar
+#[debug_handler]
+pub async fn forgot_password(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    ValidJson(payload): ValidJson<EmailRequest>,
+) -> AppResult<(StatusCode, Json<UserResponse>)> {
+    let user = sqlx::query_as::<_, Account>(&format!(
+        "SELECT {ACCOUNT_COLUMNS} FROM accounts WHERE email = $1 AND role = 'user'"
+    ))
+    .bind(&payload.email)
+    .fetch_optional(&state.pool)
+    .await?;
+
+    let event = match &user {
+        Some(user) => AuditEvent::success("user.password_reset_requested").actor(Some(user.id), &user.username),
+        // Email yang tidak terdaftar tidak disimpan (bisa saja milik orang lain yang tidak punya akun)
+        None => AuditEvent::failure("user.password_reset_requested").details(json!({ "reason": "unknown_email" })),
+    };
+    audit::record(&state.pool, &client, event).await?;
+
+    if let Some(user) = user {
+        let token = account_token::issue(&state.pool, &state.jwt, user.id, &payload.email, Purpose::PasswordReset).await?;
+        let link = format!("{}/reset-password?token={}", state.frontend_url, token);
+        mailer::send_in_background(
+            state.mailer.clone(),
+            Email {
+                to: payload.email.clone(),
+                subject: "Reset password akun ExploreMas".to_string(),
+                body: format!(
+                    "Halo {},\n\nKlik link berikut untuk membuat password baru (berlaku 60 menit, hanya bisa dipakai sekali):\n{link}\n\nAbaikan email ini kalau kamu tidak meminta reset password.",
+                    user.username
+                ),
+            },
+        );
     }
+
+    Ok((StatusCode::ACCEPTED, Json(UserResponse { message: FORGOT_PASSWORD_MESSAGE.to_string() })))
 }
 
+// POST /reset_password { "token": "...", "new_password": "..." }
 #[debug_handler]
-pub async fn login_user(
+pub async fn reset_password(
     State(state): State<AppState>,
-    Json(payload): Json<LoginRequest>,
-) -> impl IntoResponse {
-    let result = sqlx::query_as::<_, UserSql>("SELECT * FROM users WHERE username = $1")
-        .bind(&payload.username)
-        .fetch_optional(&state.pool)
-        .await;
-
-    match result {
-        Ok(Some(user)) => {
-            if verify(&payload.password, &user.password).unwrap_or(false) {
-                (StatusCode::OK, "Logged in").into_response()
-            } else {
-                (StatusCode::UNAUTHORIZED, "Login failed").into_response()
-            }
-        }
-        Ok(None) => (StatusCode::NOT_FOUND, "User not found").into_response(),
-        Err(err) => {
-            eprintln!("DB error: {:?}", err);
-            (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error").into_response()
-        }
+    client: ClientInfo,
+    ValidJson(payload): ValidJson<ResetPasswordRequest>,
+) -> AppResult<Json<UserResponse>> {
+    // Dicek dan di-hash sebelum token dipakai, supaya password yang ditolak tidak menghanguskan link reset
+    state.password_policy.check("new_password", &payload.new_password)?;
+    let hashed = password::hash(&payload.new_password)?;
+
+    // Token dipakai, password diganti dan sesi dicabut dalam satu transaksi: kalau ada yang gagal, link tetap berlaku
+    let mut tx = state.pool.begin().await?;
+    let redeemed = account_token::redeem(&mut *tx, &state.jwt, &payload.token, Purpose::PasswordReset).await?;
+
+    // Link reset sampai di inbox -> email terbukti milik user, sekalian dianggap terverifikasi
+    let username: String = sqlx::query_scalar(
+        "UPDATE accounts SET password = $1, email_verified_at = COALESCE(email_verified_at, now())
+         WHERE id = $2 RETURNING username",
+    )
+    .bind(&hashed)
+    .bind(redeemed.user_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    // Semua sesi lama (mungkin milik pencuri password) dicabut
+    session::revoke_all(&mut *tx, USER_ROLE, redeemed.user_id, None, "password_reset").await?;
+
+    let event = AuditEvent::success("user.password_reset").actor(Some(redeemed.user_id), &username);
+    audit::record(&mut *tx, &client, event).await?;
+    tx.commit().await?;
+
+    // Lockout karena salah password sebelumnya ikut dibuka
+    login_guard::record_success(&state.pool, LOGIN_SCOPE, &username).await?;
+
+    Ok(Json(UserResponse { message: "Password berhasil diganti, silakan login".to_string() }))
+}
+
+// POST /verify_email { "token": "..." }
+#[debug_handler]
+pub async fn verify_email(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    AppJson(payload): AppJson<TokenRequest>,
+) -> AppResult<Json<UserResponse>> {
+    let redeemed = account_token::redeem(&state.pool, &state.jwt, &payload.token, Purpose::VerifyEmail).await?;
+
+    let username: String = sqlx::query_scalar(
+        "UPDATE accounts SET email_verified_at = COALESCE(email_verified_at, now())
+         WHERE id = $1 AND email = $2 RETURNING username",
+    )
+    .bind(redeemed.user_id)
+    .bind(&redeemed.email)
+    .fetch_one(&state.pool)
+    .await?;
+
+    let event = AuditEvent::success("user.email_verified")
+        .actor(Some(redeemed.user_id), &username)
+        .details(json!({ "email": redeemed.email }));
+    audit::record(&state.pool, &client, event).await?;
+
+    Ok(Json(UserResponse { message: "Email berhasil diverifikasi".to_string() }))
+}
+
+// POST /resend_verification { "email": "..." } -> jawaban selalu sama (202)
+#[debug_handler]
+pub async fn resend_verification(
+    State(state): State<AppState>,
+    ValidJson(payload): ValidJson<EmailRequest>,
+) -> AppResult<(StatusCode, Json<UserResponse>)> {
+    let user = sqlx::query_as::<_, Account>(&format!(
+        "SELECT {ACCOUNT_COLUMNS} FROM accounts WHERE email = $1 AND role = 'user' AND email_verified_at IS NULL"
+    ))
+    .bind(&payload.email)
+    .fetch_optional(&state.pool)
+    .await?;
+
+    if let Some(user) = user {
+        send_verification_email(&state, user.id, &payload.email).await?;
     }
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(UserResponse { message: "Jika email terdaftar dan belum diverifikasi, link verifikasi sudah dikirim".to_string() }),
+    ))
 }
@@ -0,0 +1,108 @@
+use axum::extract::{FromRequest, Request};
+use chrono::NaiveDate;
+use serde::de::DeserializeOwned;
+use url::Url;
+use validator::{Validate, ValidationError, ValidationErrors};
+
+use crate::error::{AppError, AppJson};
+
+// Host Google Maps yang boleh dipakai di link_gmaps / gmaps
+const MAPS_HOSTS: &[&str] = &["maps.app.goo.gl", "maps.google.com", "maps.google.co.id"];
+// Host yang hanya valid kalau path-nya diawali /maps
+const MAPS_PATH_HOSTS: &[&str] = &["goo.gl", "google.com", "www.google.com", "google.co.id", "www.google.co.id"];
+// Form admin belum punya input link maps / foto, jadi kosong / "-" berarti belum ada link
+const NO_LINK: &[&str] = &["", "-"];
+
+/// Jam buka/tutup: "HH:MM" atau "HH:MM:SS" (24 jam).
+pub fn validate_time(value: &str) -> Result<(), ValidationError> {
+    let parts: Vec<&str> = value.split(':').collect();
+    let valid = matches!(parts.len(), 2 | 3)
+        && parts.iter().all(|p| p.len() == 2 && p.chars().all(|c| c.is_ascii_digit()))
+        && parts[0].parse::<u8>().is_ok_and(|h| h < 24)
+        && parts[1..].iter().all(|p| p.parse::<u8>().is_ok_and(|m| m < 60));
+
+    if valid {
+        Ok(())
+    } else {
+        Err(error("time_format", "harus berupa jam dengan format HH:MM atau HH:MM:SS"))
+    }
+}
+
+/// Link Google Maps (https) dari host yang ada di allow-list.
+pub fn validate_maps_url(value: &str) -> Result<(), ValidationError> {
+    if NO_LINK.contains(&value.trim()) {
+        return Ok(());
+    }
+
+    let allowed = Url::parse(value).ok().is_some_and(|url| {
+        let host = url.host_str().unwrap_or_default();
+        url.scheme() == "https"
+            && (MAPS_HOSTS.contains(&host)
+                || (MAPS_PATH_HOSTS.contains(&host) && url.path().starts_with("/maps")))
+    });
+
+    if allowed {
+        Ok(())
+    } else {
+        Err(error("maps_url", "harus berupa link Google Maps (https://maps.app.goo.gl/...)"))
+    }
+}
+
+/// URL gambar harus http/https absolut, atau kosong / "-" kalau belum ada foto.
+pub fn validate_image_url(value: &str) -> Result<(), ValidationError> {
+    if NO_LINK.contains(&value.trim()) {
+        return Ok(());
+    }
+
+    let valid = Url::parse(value)
+        .ok()
+        .is_some_and(|url| matches!(url.scheme(), "http" | "https") && url.host_str().is_some());
+
+    if valid {
+        Ok(())
+    } else {
+        Err(error("image_url", "harus berupa URL gambar http(s)"))
+    }
+}
+
+/// Tolak string yang isinya cuma spasi (length(min = 1) masih meloloskan "   ").
+pub fn validate_not_blank(value: &str) -> Result<(), ValidationError> {
+    if value.trim().is_empty() {
+        Err(error("required", "tidak boleh kosong"))
+    } else {
+        Ok(())
+    }
+}
+
+/// Filter tanggal di query string: `from` tidak boleh setelah `to`. Gagal -> 422 dengan field_errors di `to`.
+pub fn validate_date_range(from: Option<NaiveDate>, to: Option<NaiveDate>) -> Result<(), ValidationErrors> {
+    match (from, to) {
+        (Some(from), Some(to)) if from > to => {
+            let mut errors = ValidationErrors::new();
+            errors.add("to", error("date_range", "tidak boleh sebelum from"));
+            Err(errors)
+        }
+        _ => Ok(()),
+    }
+}
+
+fn error(code: &'static str, message: &'static str) -> ValidationError {
+    ValidationError::new(code).with_message(message.into())
+}
+
+/// Seperti AppJson, tapi payload langsung divalidasi. Gagal -> 422 dengan field_errors.
+pub struct ValidJson<T>(pub T);
+
+impl<T, S> FromRequest<S> for ValidJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let AppJson(value) = AppJson::<T>::from_request(req, state).await?;
+        value.validate()?;
+        Ok(ValidJson(value))
+    }
+}
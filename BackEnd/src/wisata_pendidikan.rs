@@ -1,21 +1,39 @@
 use crate::app_state::AppState;
-use axum::extract::{Path, State};
+use crate::error::{AppError, AppPath, AppResult};
+use crate::auth::AdminUser;
+use crate::etag::{self, IfMatch};
+use crate::revision::{self, Action};
+use crate::validation::{validate_image_url, validate_maps_url, validate_not_blank, validate_time, ValidJson};
+use axum::extract::State;
 use axum::http::StatusCode;
-use axum::response::IntoResponse;
+use axum::response::{IntoResponse, Redirect, Response};
 use axum::{debug_handler, Json};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use validator::Validate;
+
+use crate::slug;
+
+const TABLE: &str = "wisata_pendidikan";
 
 // Struct Input (Create)
-#[derive(Deserialize)]
+#[derive(Deserialize, Validate)]
 pub struct WisataSql {
+    #[validate(length(min = 1, max = 120, message = "name harus 1-120 karakter"), custom(function = "validate_not_blank"))]
     name: String,
+    #[validate(length(min = 1, max = 50, message = "category harus 1-50 karakter"), custom(function = "validate_not_blank"))]
     category: String,
+    #[validate(length(min = 1, max = 300, message = "address harus 1-300 karakter"), custom(function = "validate_not_blank"))]
     address: String,
+    #[validate(custom(function = "validate_time"))]
     open: String,
+    #[validate(custom(function = "validate_time"))]
     close: String,
+    #[validate(range(min = 0, max = 10_000_000, message = "htm harus antara 0 dan 10000000"))]
     htm: i32,
+    #[validate(length(max = 500, message = "maksimal 500 karakter"), custom(function = "validate_maps_url"))]
     gmaps: String,
+    #[validate(length(max = 500, message = "maksimal 500 karakter"), custom(function = "validate_image_url"))]
     pictures: String,
 }
 
@@ -23,6 +41,7 @@ pub struct WisataSql {
 #[derive(Serialize, FromRow)]
 pub struct WisataResponseModel {
     pub id: i32,
+    pub slug: String,
     pub nama_tempat: String,
     pub kategori: String,
     pub alamat: String,
@@ -31,75 +50,224 @@ pub struct WisataResponseModel {
     pub htm: i32,
     pub link_gmaps: String,
     pub link_foto: String,
+    pub version: i32,
 }
 
-#[derive(Serialize)]
-pub struct WisataResponse {
-    pub message: String,
+// Body PATCH: field yang tidak dikirim tidak diubah
+#[derive(Deserialize, Validate)]
+pub struct WisataPatch {
+    #[validate(length(min = 1, max = 120, message = "name harus 1-120 karakter"), custom(function = "validate_not_blank"))]
+    name: Option<String>,
+    #[validate(length(min = 1, max = 50, message = "category harus 1-50 karakter"), custom(function = "validate_not_blank"))]
+    category: Option<String>,
+    #[validate(length(min = 1, max = 300, message = "address harus 1-300 karakter"), custom(function = "validate_not_blank"))]
+    address: Option<String>,
+    #[validate(custom(function = "validate_time"))]
+    open: Option<String>,
+    #[validate(custom(function = "validate_time"))]
+    close: Option<String>,
+    #[validate(range(min = 0, max = 10_000_000, message = "htm harus antara 0 dan 10000000"))]
+    htm: Option<i32>,
+    #[validate(length(max = 500, message = "maksimal 500 karakter"), custom(function = "validate_maps_url"))]
+    gmaps: Option<String>,
+    #[validate(length(max = 500, message = "maksimal 500 karakter"), custom(function = "validate_image_url"))]
+    pictures: Option<String>,
 }
 
 #[debug_handler]
 pub async fn create_wisata_pendidikan(
     State(state): State<AppState>,
-    Json(payload): Json<WisataSql>,
-) -> impl IntoResponse {
-    let result = sqlx::query(
-        "insert into wisata_pendidikan(nama_tempat, kategori, alamat, jam_buka, jam_tutup, htm, link_gmaps, link_foto)
-        values ($1, $2, $3, $4, $5, $6, $7, $8)")
-        .bind(&payload.name)
-        .bind(&payload.category)
-        .bind(&payload.address)
-        .bind(&payload.open)
-        .bind(&payload.close)
-        .bind(&payload.htm)
-        .bind(&payload.gmaps)
-        .bind(&payload.pictures)
-        .execute(&state.pool)
+    admin: AdminUser,
+    ValidJson(payload): ValidJson<WisataSql>,
+) -> AppResult<(StatusCode, Json<WisataResponseModel>)> {
+    // Slug, INSERT dan revisi dalam satu transaksi, diulang kalau slug keburu dipakai request lain
+    let mut attempt = 1;
+    let created = loop {
+        let mut tx = state.pool.begin().await?;
+        let result = async {
+            let new_slug = slug::unique_slug(&mut tx, TABLE, &payload.name, None).await?;
+
+            let created = sqlx::query_as::<_, WisataResponseModel>(
+                "insert into wisata_pendidikan(nama_tempat, kategori, alamat, jam_buka, jam_tutup, htm, link_gmaps, link_foto, slug)
+                values ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                returning *")
+                .bind(&payload.name)
+                .bind(&payload.category)
+                .bind(&payload.address)
+                .bind(&payload.open)
+                .bind(&payload.close)
+                .bind(payload.htm)
+                .bind(&payload.gmaps)
+                .bind(&payload.pictures)
+                .bind(&new_slug)
+                .fetch_one(&mut *tx)
+                .await?;
+
+            revision::record(&mut tx, TABLE, created.id, Action::Create, &admin, None).await?;
+            Ok(created)
+        }
         .await;
+        if let Some(created) = slug::finish(tx, result, &mut attempt).await? {
+            break created;
+        }
+    };
 
-    match result {
-        Ok(_) => (
-            StatusCode::OK,
-            Json(WisataResponse {
-                message: "Wisata created".to_string(),
-            }),
-        ),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(WisataResponse {
-                message: format!("error: {}", e),
-            }),
-        ),
-    }
+    state.sitemap.invalidate();
+    Ok((StatusCode::CREATED, Json(created)))
 }
 
-// PERBAIKAN: fetch_one -> fetch_all
 #[debug_handler]
-pub async fn get_wisata_pendidikan(State(state): State<AppState>) -> impl IntoResponse {
-    let result = sqlx::query_as::<_, WisataResponseModel>("select * from wisata_pendidikan")
-        .fetch_all(&state.pool) // GANTI fetch_one JADI fetch_all
-        .await;
+pub async fn get_wisata_pendidikan(State(state): State<AppState>) -> AppResult<Json<Vec<WisataResponseModel>>> {
+    let data = sqlx::query_as::<_, WisataResponseModel>("select * from wisata_pendidikan WHERE deleted_at IS NULL ORDER BY id")
+        .fetch_all(&state.pool)
+        .await?;
 
-    match result {
-        Ok(data) => Json(data).into_response(),
-        Err(err) => {
-            eprintln!("Db error get_wisata_pendidikan: {:?}", err);
-            StatusCode::INTERNAL_SERVER_ERROR.into_response()
-        }
+    Ok(Json(data))
+}
+
+pub async fn get_wisata_pendidikan_by_id(State(state): State<AppState>, AppPath(id): AppPath<i32>) -> AppResult<Response> {
+    let data = sqlx::query_as::<_, WisataResponseModel>(
+        "SELECT * FROM wisata_pendidikan WHERE id = $1 AND deleted_at IS NULL"
+    ).bind(id).fetch_optional(&state.pool).await?
+    .ok_or_else(|| AppError::not_found("ID tidak ditemukan"))?;
+
+    Ok(etag::tagged(data.version, data))
+}
+
+// GET /wisata_pendidikan/slug/{slug} -> slug lama di-redirect ke slug terbaru
+pub async fn get_wisata_pendidikan_by_slug(State(state): State<AppState>, AppPath(slug_param): AppPath<String>) -> AppResult<Response> {
+    let result = sqlx::query_as::<_, WisataResponseModel>("SELECT * FROM wisata_pendidikan WHERE slug = $1 AND deleted_at IS NULL")
+    .bind(&slug_param).fetch_optional(&state.pool).await?;
+
+    if let Some(data) = result {
+        return Ok(etag::tagged(data.version, data));
+    }
+
+    match slug::find_redirect(&state.pool, TABLE, &slug_param).await? {
+        Some(current) => Ok(Redirect::permanent(&format!("/wisata_pendidikan/slug/{current}")).into_response()),
+        None => Err(AppError::not_found("Slug tidak ditemukan")),
     }
 }
 
-pub async fn get_wisata_pendidikan_by_id(State(state): State<AppState>, Path(id): Path<i32>) -> impl IntoResponse {
-    let result = sqlx::query_as::<_, WisataResponseModel>(
-        "SELECT * FROM wisata_pendidikan WHERE id = $1"
-    ).bind(id).fetch_optional(&state.pool).await;
-
-    match result {
-        Ok(Some(data)) => Json(data).into_response(),
-        Ok(None) => (StatusCode::NOT_FOUND, "Not found").into_response(),
-        Err(err) => {
-            eprintln!("DB error: {:?}", err);
-            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+// --- BARU: UPDATE ---
+// PUT = payload lengkap. If-Match opsional, kalau dikirim harus sama dengan ETag terakhir.
+pub async fn update_wisata_pendidikan(
+    State(state): State<AppState>,
+    admin: AdminUser,
+    AppPath(id): AppPath<i32>,
+    IfMatch(expected): IfMatch,
+    ValidJson(payload): ValidJson<WisataSql>,
+) -> AppResult<Response> {
+    let updated = save_wisata_pendidikan(&state, &admin, id, payload, expected).await?;
+    Ok(etag::tagged(updated.version, updated))
+}
+
+// PATCH = hanya field yang berubah, sisanya diambil dari data sekarang.
+// Tanpa If-Match tetap dikunci ke versi yang dibaca, supaya perubahan admin lain di antaranya tidak tertimpa
+pub async fn patch_wisata_pendidikan(
+    State(state): State<AppState>,
+    admin: AdminUser,
+    AppPath(id): AppPath<i32>,
+    IfMatch(expected): IfMatch,
+    ValidJson(patch): ValidJson<WisataPatch>,
+) -> AppResult<Response> {
+    let current = sqlx::query_as::<_, WisataResponseModel>("SELECT * FROM wisata_pendidikan WHERE id = $1 AND deleted_at IS NULL")
+        .bind(id)
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or_else(|| AppError::not_found("ID tidak ditemukan"))?;
+
+    let payload = WisataSql {
+        name: patch.name.unwrap_or(current.nama_tempat),
+        category: patch.category.unwrap_or(current.kategori),
+        address: patch.address.unwrap_or(current.alamat),
+        open: patch.open.unwrap_or(current.jam_buka),
+        close: patch.close.unwrap_or(current.jam_tutup),
+        htm: patch.htm.unwrap_or(current.htm),
+        gmaps: patch.gmaps.unwrap_or(current.link_gmaps),
+        pictures: patch.pictures.unwrap_or(current.link_foto),
+    };
+
+    let updated = save_wisata_pendidikan(&state, &admin, id, payload, expected.or(Some(current.version))).await?;
+    Ok(etag::tagged(updated.version, updated))
+}
+
+async fn save_wisata_pendidikan(
+    state: &AppState,
+    admin: &AdminUser,
+    id: i32,
+    payload: WisataSql,
+    expected: Option<i32>,
+) -> AppResult<WisataResponseModel> {
+    // Rename -> slug baru, slug lama tetap jalan lewat redirect. Cek versi, slug, redirect, UPDATE dan revisi
+    // satu transaksi: kalau UPDATE gagal (412 / 404) redirect dan revisi ikut batal
+    let mut attempt = 1;
+    let updated = loop {
+        let mut tx = state.pool.begin().await?;
+        let result = async {
+            let before = revision::snapshot(&mut tx, TABLE, id).await?;
+            etag::check(&mut tx, TABLE, id, expected).await?;
+
+            let new_slug = slug::slug_for_update(&mut tx, TABLE, id, &payload.name)
+                .await?
+                .ok_or_else(|| AppError::not_found("ID tidak ditemukan"))?;
+
+            let updated = sqlx::query_as::<_, WisataResponseModel>(
+                r#"UPDATE wisata_pendidikan
+                   SET nama_tempat=$1, kategori=$2, alamat=$3, jam_buka=$4, jam_tutup=$5, htm=$6, link_gmaps=$7, link_foto=$8, slug=$10, updated_at=now(), version=version+1
+                   WHERE id=$9 AND deleted_at IS NULL AND ($11::int IS NULL OR version=$11)
+                   RETURNING *"#
+            )
+            .bind(&payload.name)
+            .bind(&payload.category)
+            .bind(&payload.address)
+            .bind(&payload.open)
+            .bind(&payload.close)
+            .bind(payload.htm)
+            .bind(&payload.gmaps)
+            .bind(&payload.pictures)
+            .bind(id)
+            .bind(&new_slug)
+            .bind(expected)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+            let Some(updated) = updated else {
+                return Err(etag::missing_or_stale(&mut tx, TABLE, id).await);
+            };
+            revision::record(&mut tx, TABLE, id, Action::Update, admin, before).await?;
+            Ok(updated)
         }
+        .await;
+        if let Some(updated) = slug::finish(tx, result, &mut attempt).await? {
+            break updated;
+        }
+    };
+
+    state.sitemap.invalidate();
+    Ok(updated)
+}
+
+// --- BARU: DELETE ---
+pub async fn delete_wisata_pendidikan(
+    State(state): State<AppState>,
+    admin: AdminUser,
+    AppPath(id): AppPath<i32>,
+) -> AppResult<StatusCode> {
+    // Snapshot, soft delete dan revisi satu transaksi
+    let mut tx = state.pool.begin().await?;
+    let before = revision::snapshot(&mut tx, TABLE, id).await?;
+    let res = sqlx::query("UPDATE wisata_pendidikan SET deleted_at = now() WHERE id = $1 AND deleted_at IS NULL")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+    if res.rows_affected() == 0 {
+        return Err(AppError::not_found("ID tidak ditemukan"));
     }
-}
\ No newline at end of file
+
+    revision::record(&mut tx, TABLE, id, Action::Delete, &admin, before).await?;
+    tx.commit().await?;
+    state.sitemap.invalidate();
+    Ok(StatusCode::NO_CONTENT)
+}
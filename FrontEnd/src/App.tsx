@@ -14,6 +14,9 @@ import TransBanyumas from "./pages/TransBanyumas";
 import WisataPage from "./pages/WisataPage";
 import WisataDetail from "./pages/WisataDetail";
 
+// BERITA
+import NewsDetail from "./pages/NewsDetail";
+
 // CHAT
 import ChatButton from "./components/chat/ChatButton";
 import ChatPopup from "./components/chat/ChatPopup";
@@ -28,6 +31,8 @@ import AdminLoginPage from "./pages/AdminLoginPage"; // <--- TAMBAHAN PENTING
 // AUTH
 import LoginPage from "./pages/LoginPage";
 import RegisterPage from "./pages/RegisterPage";
+import ResetPasswordPage from "./pages/ResetPasswordPage";
+import VerifyEmailPage from "./pages/VerifyEmailPage";
 
 function App() {
   return (
@@ -39,6 +44,9 @@ function App() {
         {/* AUTH USER BIASA */}
         <Route path="/login" element={<LoginPage />} />
         <Route path="/register" element={<RegisterPage />} />
+        {/* Link dari email (lupa password & verifikasi), token di query string */}
+        <Route path="/reset-password" element={<ResetPasswordPage />} />
+        <Route path="/verify-email" element={<VerifyEmailPage />} />
 
         {/* === ADMIN AREA (HIDDEN/RAHASIA) === */}
         {/* Akses manual di browser: localhost:5173/loginadmin */}
@@ -53,6 +61,9 @@ function App() {
         <Route path="/wisata" element={<WisataPage />} />
         <Route path="/wisata/:slug" element={<WisataDetail />} />
 
+        {/* BERITA (link dari Home, RSS/Atom dan sitemap) */}
+        <Route path="/news/:slug" element={<NewsDetail />} />
+
         {/* TRANS BANYUMAS */}
         <Route path="/trans" element={<TransBanyumas />} />
 
@@ -1,12 +1,14 @@
 import { useEffect, useState } from "react";
+import { Link } from "react-router-dom";
 import { Calendar, Clock, ArrowRight } from "lucide-react";
 
 // Tipe Data Berita
 type NewsItem = {
   id: number;
+  slug: string;
   title: string;
   category: string;
-  date: string;
+  published_at: string; // ISO 8601 dari server
   image_url: string;
   content: string;
   read_minutes: number;
@@ -61,46 +63,48 @@ export default function NewsList() {
         {/* Grid Berita */}
         <div className="grid grid-cols-1 md:grid-cols-2 lg:grid-cols-2 gap-8">
           {news.map((item) => (
-            <article key={item.id} className="group bg-white rounded-3xl overflow-hidden shadow-sm hover:shadow-xl transition-all duration-300 border border-slate-100 flex flex-col md:flex-row h-full md:h-[200px]">
+            <Link key={item.id} to={`/news/${item.slug}`} className="block h-full">
+              <article className="group bg-white rounded-3xl overflow-hidden shadow-sm hover:shadow-xl transition-all duration-300 border border-slate-100 flex flex-col md:flex-row h-full md:h-[200px]">
               
-              {/* Gambar (Sebelah kiri/atas) */}
-              <div className="md:w-5/12 h-48 md:h-full relative overflow-hidden">
-                <img 
-                  src={item.image_url} 
-                  alt={item.title}
-                  className="w-full h-full object-cover transform group-hover:scale-110 transition duration-500"
-                />
-                <div className="absolute top-3 left-3 bg-white/90 backdrop-blur px-3 py-1 rounded-full text-[10px] font-bold uppercase tracking-wider text-indigo-600">
-                  {item.category}
+                {/* Gambar (Sebelah kiri/atas) */}
+                <div className="md:w-5/12 h-48 md:h-full relative overflow-hidden">
+                  <img 
+                    src={item.image_url} 
+                    alt={item.title}
+                    className="w-full h-full object-cover transform group-hover:scale-110 transition duration-500"
+                  />
+                  <div className="absolute top-3 left-3 bg-white/90 backdrop-blur px-3 py-1 rounded-full text-[10px] font-bold uppercase tracking-wider text-indigo-600">
+                    {item.category}
+                  </div>
                 </div>
-              </div>
 
-              {/* Konten (Sebelah kanan/bawah) */}
-              <div className="p-6 md:w-7/12 flex flex-col justify-center">
-                <div className="flex items-center gap-4 text-xs text-slate-400 mb-3">
-                  <span className="flex items-center gap-1">
-                    <Calendar className="w-3 h-3"/> {item.date}
-                  </span>
-                  <span className="flex items-center gap-1">
-                    <Clock className="w-3 h-3"/> {item.read_minutes} min read
-                  </span>
-                </div>
+                {/* Konten (Sebelah kanan/bawah) */}
+                <div className="p-6 md:w-7/12 flex flex-col justify-center">
+                  <div className="flex items-center gap-4 text-xs text-slate-400 mb-3">
+                    <span className="flex items-center gap-1">
+                      <Calendar className="w-3 h-3"/> {new Date(item.published_at).toLocaleDateString("id-ID", { day: "numeric", month: "long", year: "numeric" })}
+                    </span>
+                    <span className="flex items-center gap-1">
+                      <Clock className="w-3 h-3"/> {item.read_minutes} min read
+                    </span>
+                  </div>
 
-                <h3 className="text-lg font-bold text-slate-800 mb-2 leading-tight group-hover:text-indigo-600 transition">
-                  {item.title}
-                </h3>
+                  <h3 className="text-lg font-bold text-slate-800 mb-2 leading-tight group-hover:text-indigo-600 transition">
+                    {item.title}
+                  </h3>
 
-                <p className="text-sm text-slate-500 line-clamp-2 mb-4">
-                  {item.content}
-                </p>
+                  <p className="text-sm text-slate-500 line-clamp-2 mb-4">
+                    {item.content}
+                  </p>
 
-                <div className="mt-auto">
-                  <span className="text-xs font-bold text-indigo-600 flex items-center group-hover:gap-2 transition-all">
-                    Baca Selengkapnya <ArrowRight className="w-3 h-3 ml-1"/>
-                  </span>
+                  <div className="mt-auto">
+                    <span className="text-xs font-bold text-indigo-600 flex items-center group-hover:gap-2 transition-all">
+                      Baca Selengkapnya <ArrowRight className="w-3 h-3 ml-1"/>
+                    </span>
+                  </div>
                 </div>
-              </div>
-            </article>
+              </article>
+            </Link>
           ))}
         </div>
 
@@ -110,9 +110,14 @@ export function ChatProvider({ children }: ChatProviderProps) {
   // ==========================================
   const logToBackend = async (question: string, answer: string) => {
     try {
+      // Kalau user login, chat dicatat atas nama akunnya (ikut terhapus saat akun dihapus)
+      const userToken = localStorage.getItem("user_token");
       await fetch(`${API_BASE}/api/chat/log`, {
         method: "POST",
-        headers: { "Content-Type": "application/json" },
+        headers: {
+          "Content-Type": "application/json",
+          ...(userToken ? { Authorization: `Bearer ${userToken}` } : {}),
+        },
         body: JSON.stringify({
           question: question,
           answer: answer
@@ -11,6 +11,11 @@ import ChatbotAdminPanel from "../components/chatbot/ChatbotAdminPanel";
 
 const API_BASE = import.meta.env.VITE_API_BASE_URL || "http://localhost:3000"; 
 
+// Token JWT dari /admin_login, wajib dikirim ke endpoint admin (tambah/edit/hapus)
+const authHeaders = (): Record<string, string> => ({
+  Authorization: `Bearer ${localStorage.getItem("admin_token") || ""}`,
+});
+
 // === KONFIGURASI TAGS / FASILITAS ===
 const CAFE_TAGS = ["wifi", "socket", "ac", "24h", "parking", "studyFriendly"];
 const WISATA_TAGS = ["parking", "cheap", "instagrammable", "nature", "waterpark"];
@@ -34,6 +39,7 @@ type AdminPlace = {
   openTime?: string;
   closeTime?: string;
   tags?: string[];
+  version?: number; // dikirim balik sebagai If-Match saat update
 };
 
 type PlaceForm = {
@@ -51,7 +57,7 @@ type NewsItem = {
   id: number;
   title: string;
   category: string;
-  date: string;
+  published_at: string; // ISO 8601 dari server
   image_url: string;
   content: string;
   read_minutes: number;
@@ -65,13 +71,30 @@ type NewsForm = {
   content: string;
 };
 
+// /api/news di-paging (maks 100 per halaman), total datanya ada di header X-Total-Count
+const fetchAllNews = async (): Promise<NewsItem[]> => {
+  const all: NewsItem[] = [];
+  for (let page = 1; ; page++) {
+    const res = await fetch(`${API_BASE}/api/news?per_page=100&page=${page}`);
+    if (!res.ok) break;
+    const rows: NewsItem[] = await res.json();
+    all.push(...rows);
+    const total = Number(res.headers.get("X-Total-Count") || 0);
+    if (rows.length === 0 || all.length >= total) break;
+  }
+  return all;
+};
+
+const formatDate = (iso: string) =>
+  new Date(iso).toLocaleDateString("id-ID", { day: "numeric", month: "short", year: "numeric" });
+
 export default function AdminDashboard() {
   const navigate = useNavigate();
 
   // === 🛡️ SECURITY CHECK ===
   useEffect(() => {
     const role = localStorage.getItem("role");
-    if (role !== "admin") {
+    if (role !== "admin" || !localStorage.getItem("admin_token")) {
       navigate("/loginadmin", { replace: true });
     }
   }, [navigate]);
@@ -87,6 +110,7 @@ export default function AdminDashboard() {
   const [searchQuery, setSearchQuery] = useState("");
   const [isEditing, setIsEditing] = useState(false);
   const [editId, setEditId] = useState<number | null>(null);
+  const [editVersion, setEditVersion] = useState<number | null>(null);
   
   // Initial State dengan Jam
   const [placeForm, setPlaceForm] = useState<PlaceForm>({
@@ -111,25 +135,24 @@ export default function AdminDashboard() {
       const mergedPlaces: AdminPlace[] = [
         ...(resWisata.ok ? await resWisata.json() : []).map((i:any) => ({ 
             id: i.id, name: i.nama_tempat, category: "Wisata Alam", address: i.alamat, imageUrl: i.link_foto, price: i.htm, 
-            openTime: i.jam_buka, closeTime: i.jam_tutup, tags: i.tags 
+            openTime: i.jam_buka, closeTime: i.jam_tutup, tags: i.tags, version: i.version 
         })),
         ...(resPendidikan.ok ? await resPendidikan.json() : []).map((i:any) => ({ 
             id: i.id, name: i.nama_tempat, category: "Wisata Pendidikan", address: i.alamat, imageUrl: i.link_foto, price: i.htm,
-            openTime: i.jam_buka, closeTime: i.jam_tutup, tags: i.tags
+            openTime: i.jam_buka, closeTime: i.jam_tutup, tags: i.tags, version: i.version
         })),
         ...(resCafe.ok ? await resCafe.json() : []).map((i:any) => ({ 
             id: i.id, name: i.nama_tempat, category: "Cafe", address: i.alamat, imageUrl: i.link_foto, price: i.htm,
-            openTime: i.jam_buka, closeTime: i.jam_tutup, tags: i.tags
+            openTime: i.jam_buka, closeTime: i.jam_tutup, tags: i.tags, version: i.version
         })),
         ...(resKuliner.ok ? await resKuliner.json() : []).map((i:any) => ({ 
             id: i.id, name: i.nama_tempat, category: "Kuliner", address: i.alamat, imageUrl: i.link_foto, price: i.htm,
-            openTime: i.jam_buka, closeTime: i.jam_tutup, tags: i.tags
+            openTime: i.jam_buka, closeTime: i.jam_tutup, tags: i.tags, version: i.version
         })),
       ];
       setPlaces(mergedPlaces);
 
-      const resNews = await fetch(`${API_BASE}/api/news`);
-      if (resNews.ok) setNewsList(await resNews.json());
+      setNewsList(await fetchAllNews());
 
     } catch (err) {
       console.error("Gagal ambil data:", err);
@@ -220,18 +243,24 @@ export default function AdminDashboard() {
         throw new Error("Kategori wajib dipilih"); 
       }
 
-      const res = await fetch(endpoint, { method, headers: { "Content-Type": "application/json" }, body: JSON.stringify(payload) });
+      // Update dikunci ke versi yang sedang diedit, server menolak (412) kalau sudah diubah admin lain
+      const ifMatch: Record<string, string> = isEditing && editVersion !== null ? { "If-Match": `"${editVersion}"` } : {};
+      const res = await fetch(endpoint, { method, headers: { "Content-Type": "application/json", ...ifMatch, ...authHeaders() }, body: JSON.stringify(payload) });
+      if (res.status === 412) {
+        fetchData();
+        throw new Error("Data ini sudah diubah admin lain. Data terbaru sudah dimuat, silakan edit ulang.");
+      }
       if (!res.ok) throw new Error("Gagal menyimpan tempat. Cek koneksi backend.");
 
       setSuccessMsg(isEditing ? "Data berhasil diupdate!" : "Data berhasil ditambahkan!");
-      setIsEditing(false); setEditId(null);
+      setIsEditing(false); setEditId(null); setEditVersion(null);
       setPlaceForm({ name: "", category: "", address: "", imageUrl: "", price: "0", openTime: "", closeTime: "", tags: [] });
       fetchData();
     } catch (err: any) { setError(err.message); } finally { setIsLoading(false); }
   };
 
   const handleEditPlace = (p: AdminPlace) => {
-    setIsEditing(true); setEditId(p.id); setActiveTab("places");
+    setIsEditing(true); setEditId(p.id); setEditVersion(p.version ?? null); setActiveTab("places");
     setPlaceForm({ 
         name: p.name, 
         category: p.category, 
@@ -255,7 +284,7 @@ export default function AdminDashboard() {
     else if(cat==="Kuliner") endpoint=`${API_BASE}/api/delete_kuliner/${id}`;
 
     if(endpoint) {
-        await fetch(endpoint, { method: "DELETE" });
+        await fetch(endpoint, { method: "DELETE", headers: authHeaders() });
         setSuccessMsg("Data terhapus."); fetchData();
     }
   };
@@ -281,7 +310,7 @@ export default function AdminDashboard() {
 
       const res = await fetch(`${API_BASE}/api/news`, {
         method: "POST",
-        headers: { "Content-Type": "application/json" },
+        headers: { "Content-Type": "application/json", ...authHeaders() },
         body: JSON.stringify(payload)
       });
 
@@ -295,13 +324,17 @@ export default function AdminDashboard() {
 
   const handleDeleteNews = async (id: number) => {
     if(!confirm("Hapus berita ini?")) return;
-    await fetch(`${API_BASE}/api/news/${id}`, { method: "DELETE" });
+    await fetch(`${API_BASE}/api/news/${id}`, { method: "DELETE", headers: authHeaders() });
     setSuccessMsg("Berita dihapus."); fetchData();
   };
 
-  const handleLogout = () => {
+  const handleLogout = async () => {
     if(confirm("Keluar admin?")) { 
+      // Cabut sesi di server supaya token ini tidak bisa dipakai lagi
+      await fetch(`${API_BASE}/logout`, { method: "POST", headers: authHeaders() }).catch(() => {});
       localStorage.removeItem("role"); 
+      localStorage.removeItem("admin_token");
+      localStorage.removeItem("admin_refresh_token");
       navigate("/loginadmin"); 
     }
   };
@@ -460,7 +493,7 @@ export default function AdminDashboard() {
                     {newsList.map(n => (
                       <tr key={n.id} className="border-b last:border-0 hover:bg-slate-50">
                         <td className="p-2 font-medium">{n.title}<div className="text-[10px] text-slate-400">{n.category}</div></td>
-                        <td className="p-2 text-xs text-slate-500">{n.date}</td>
+                        <td className="p-2 text-xs text-slate-500">{formatDate(n.published_at)}</td>
                         <td className="p-2 text-right">
                           <button onClick={()=>handleDeleteNews(n.id)} className="p-1 text-red-500 hover:bg-red-50 rounded"><Trash2 className="w-3.5 h-3.5"/></button>
                         </td>
@@ -39,15 +39,47 @@ const AdminLoginPage: React.FC = () => {
         }),
       });
 
+      if (response.status === 429) {
+        const retryAfter = response.headers.get("Retry-After");
+        setError(`Terlalu banyak percobaan login. Coba lagi dalam ${retryAfter ?? "beberapa"} detik.`);
+        return;
+      }
+
       if (!response.ok) {
         throw new Error("Login gagal. Cek username/password admin.");
       }
 
-      const data = await response.text();
-      console.log("Admin Login Success:", data);
+      let data = await response.json();
+
+      // Admin dengan 2FA: password benar, lalu minta kode dari aplikasi authenticator (atau recovery code)
+      if (data.mfa_required) {
+        const code = window.prompt("Masukkan kode 2FA (6 digit) atau recovery code:");
+        if (!code) {
+          setError("Login dibatalkan, kode 2FA diperlukan.");
+          return;
+        }
+        const verify = await fetch(`${API_BASE}/admin_login/verify`, {
+          method: "POST",
+          headers: { "Content-Type": "application/json" },
+          body: JSON.stringify({ mfa_token: data.mfa_token, code: code.trim() }),
+        });
+        if (!verify.ok) {
+          setError("Kode 2FA salah atau sesi login sudah kedaluwarsa.");
+          return;
+        }
+        data = await verify.json();
+      }
+
+      // /login dan /admin_login sama untuk semua akun, akun user biasa tidak boleh masuk dashboard
+      if (data.role !== "admin") {
+        setError("Akun ini bukan akun admin.");
+        return;
+      }
 
-      // Simpan penanda bahwa admin sedang login
+      // Simpan penanda bahwa admin sedang login + token untuk endpoint admin
       localStorage.setItem("role", "admin");
+      localStorage.setItem("admin_token", data.token);
+      localStorage.setItem("admin_refresh_token", data.refresh_token);
 
       alert("Welcome Admin! Mengalihkan ke Dashboard...");
       navigate("/admin"); // Redirect ke Dashboard Admin
@@ -15,7 +15,7 @@ import {
 
 const CafeDetail: React.FC = () => {
   const navigate = useNavigate();
-  const { slug } = useParams<{ slug: string }>(); // slug berisi Unique ID (contoh: CAFE-5 atau KUL-30)
+  const { slug } = useParams<{ slug: string }>(); // "<PREFIX>-<slug>" (contoh: CAFE-kopi-senja), link lama "<PREFIX>-<id>" (KUL-30) tetap jalan
   const [data, setData] = useState<any>(null);
   const [loading, setLoading] = useState(true);
   
@@ -28,25 +28,32 @@ const CafeDetail: React.FC = () => {
       try {
         setLoading(true);
         
-        // Memecah Unique ID menjadi Prefix dan ID Database asli
-        const parts = slug.split("-");
-        if (parts.length < 2) {
+        // Memecah "<PREFIX>-<slug atau id>", slug sendiri boleh mengandung "-"
+        const dash = slug.indexOf("-");
+        if (dash < 1) {
             setLoading(false);
             return;
         }
         
-        const prefix = parts[0]; // "CAFE" atau "KUL"
-        const id = parts[1];     // ID asli (integer)
+        const prefix = slug.slice(0, dash); // "CAFE" atau "KUL"
+        const key = slug.slice(dash + 1);
         
         // Menentukan endpoint berdasarkan prefix
-        const endpoint = prefix === "KUL" ? "get_kuliner" : "tempat_nongkrong";
+        const endpoint = prefix === "KUL" ? "kuliner" : "tempat_nongkrong";
 
-        const response = await fetch(`${API_BASE}/${endpoint}`);
-        const list = await response.json();
-        
-        // Mencari data yang ID-nya cocok
-        const found = list.find((item: any) => String(item.id) === String(id));
+        // Slug dulu (slug lama di-redirect server ke slug terbaru), angka yang bukan slug = id dari link lama
+        let response = await fetch(`${API_BASE}/${endpoint}/slug/${encodeURIComponent(key)}`);
+        if (!response.ok && /^\d+$/.test(key)) {
+          response = await fetch(`${API_BASE}/${endpoint}/${key}`);
+        }
+        if (!response.ok) {
+          setData(null);
+          return;
+        }
+        const found = await response.json();
         setData(found);
+        // Selalu tampilkan URL kanonik (sama dengan sitemap)
+        if (found.slug && found.slug !== key) navigate(`/cafes/${prefix}-${found.slug}`, { replace: true });
       } catch (e) {
         console.error("Error fetching detail:", e);
       } finally {
@@ -55,7 +62,7 @@ const CafeDetail: React.FC = () => {
     };
 
     fetchDetail();
-  }, [slug, API_BASE]);
+  }, [slug, API_BASE, navigate]);
 
   // Fungsi Helper untuk membuka Google Maps
   const handleOpenMaps = () => {
@@ -13,6 +13,7 @@ type Facility = string;
 type CafeUI = {
   uniqueId: string;
   id: string;
+  detailPath: string; // /cafes/CAFE-<slug>, sama dengan URL di sitemap
   name: string;
   description: string;
   imageUrl: string;
@@ -93,6 +94,7 @@ const CafePage: React.FC = () => {
           const kategori = pickString(raw, ["kategori"], prefix === "CAFE" ? "tempat nongkrong" : "kuliner");
           return {
             id: String(raw.id),
+            detailPath: `/cafes/${prefix}-${raw.slug ?? raw.id}`,
             name: pickString(raw, ["nama_tempat", "name"]),
             description: raw.deskripsi && raw.deskripsi !== "-" ? raw.deskripsi : `Nikmati suasana terbaik di ${raw.nama_tempat}`,
             imageUrl: raw.link_foto,
@@ -215,7 +217,7 @@ const CafePage: React.FC = () => {
           <div className="mt-8 md:mt-10">
             <div className="grid grid-cols-1 sm:grid-cols-2 lg:grid-cols-3 gap-6 md:gap-8">
               {filteredCafes.slice(0, visibleLimit).map((cafe) => (
-                <Link key={cafe.uniqueId} to={`/cafes/${cafe.uniqueId}`} className="group h-full">
+                <Link key={cafe.uniqueId} to={cafe.detailPath} className="group h-full">
                   <article className="bg-white rounded-[24px] md:rounded-[32px] shadow-sm hover:shadow-xl hover:-translate-y-1 transition-all duration-300 overflow-hidden flex flex-col h-full border border-slate-100">
                     <div className="h-52 md:h-60 overflow-hidden relative">
                       <img 
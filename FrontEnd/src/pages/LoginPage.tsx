@@ -41,18 +41,28 @@ const LoginPage: React.FC = () => {
       });
 
       // 2. Cek Jawaban Backend
+      if (response.status === 429) {
+        // Terlalu banyak percobaan gagal, backend kasih tahu sisa waktu tunggu (detik)
+        const retryAfter = response.headers.get("Retry-After");
+        setBackendError(`Terlalu banyak percobaan login. Coba lagi dalam ${retryAfter ?? "beberapa"} detik.`);
+        return;
+      }
+
       if (!response.ok) {
-        // Jika 401 (Salah password) atau 404 (User tidak ada)
+        // 401: email/password salah (backend tidak membedakan user tidak ada dan password salah)
         const errorMessage = await response.text(); 
         throw new Error(errorMessage || "Login gagal. Periksa email/password.");
       }
 
       // 3. Jika Sukses (200 OK)
-      const data = await response.text(); // Backend membalas text "Logged in"
-      console.log("Login Success:", data);
-
-      // Simpan status login (opsional, bisa pakai localStorage)
-      // localStorage.setItem("isLoggedIn", "true");
+      // Backend membalas token JWT, dipakai untuk /api/me (profil, ganti password, hapus akun)
+      const data = await response.json();
+      localStorage.setItem("user_token", data.token);
+      // Refresh token untuk POST /refresh saat token habis
+      localStorage.setItem("user_refresh_token", data.refresh_token);
+      if (response.headers.get("X-Email-Verified") === "false") {
+        console.log("Email akun belum diverifikasi");
+      }
 
       alert("Login Berhasil!");
       navigate("/"); // PINDAH KE HALAMAN HOME
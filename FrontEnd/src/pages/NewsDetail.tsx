@@ -0,0 +1,122 @@
+import React, { useEffect, useState } from "react";
+import { useNavigate, useParams } from "react-router-dom";
+import { FiArrowLeft, FiCalendar, FiClock } from "react-icons/fi";
+
+type NewsDetailData = {
+  id: number;
+  slug: string;
+  title: string;
+  category: string;
+  image_url: string;
+  content_html: string; // HTML hasil render Markdown, sudah disanitasi server
+  published_at: string;
+  read_minutes: number;
+};
+
+const NewsDetail: React.FC = () => {
+  const navigate = useNavigate();
+  const { slug } = useParams<{ slug: string }>(); // slug berita (link dari Home, RSS/Atom dan sitemap)
+  const [data, setData] = useState<NewsDetailData | null>(null);
+  const [loading, setLoading] = useState(true);
+
+  const API_BASE = import.meta.env.VITE_API_BASE_URL || "http://localhost:3000";
+
+  useEffect(() => {
+    if (!slug) return;
+
+    const fetchDetail = async () => {
+      try {
+        setLoading(true);
+        // Slug lama di-redirect server ke slug terbaru, fetch mengikuti redirect otomatis
+        const response = await fetch(`${API_BASE}/api/news/slug/${encodeURIComponent(slug)}`);
+        if (!response.ok) {
+          setData(null);
+          return;
+        }
+        const found: NewsDetailData = await response.json();
+        setData(found);
+        if (found.slug !== slug) navigate(`/news/${found.slug}`, { replace: true });
+      } catch (e) {
+        console.error("Error fetching news:", e);
+      } finally {
+        setLoading(false);
+      }
+    };
+
+    fetchDetail();
+  }, [slug, API_BASE, navigate]);
+
+  if (loading) {
+    return (
+      <div className="min-h-screen flex items-center justify-center bg-slate-50">
+        <div className="animate-pulse text-slate-400 font-medium">Memuat berita...</div>
+      </div>
+    );
+  }
+
+  if (!data) {
+    return (
+      <main className="min-h-screen bg-slate-50 flex flex-col items-center justify-center p-6 text-center">
+        <div className="bg-white p-8 rounded-3xl shadow-sm max-w-sm w-full border border-slate-100">
+          <p className="text-red-500 font-bold mb-2">Berita Tidak Ditemukan</p>
+          <p className="text-slate-500 text-sm mb-6">Maaf, berita ini tidak tersedia atau sudah dihapus.</p>
+          <button
+            onClick={() => navigate("/")}
+            className="w-full bg-slate-900 text-white px-6 py-2 rounded-full text-sm font-medium hover:bg-black transition"
+          >
+            Kembali ke Beranda
+          </button>
+        </div>
+      </main>
+    );
+  }
+
+  return (
+    <main className="min-h-screen bg-slate-100 px-4 py-6 md:py-10">
+      <div className="mx-auto w-full max-w-3xl">
+        {/* Tombol kembali */}
+        <button
+          onClick={() => navigate(-1)}
+          className="mb-6 inline-flex items-center gap-2 rounded-full border border-slate-200 bg-white px-5 py-2 text-sm font-semibold text-slate-700 shadow-sm hover:bg-slate-50 transition"
+        >
+          <FiArrowLeft />
+          <span>Kembali</span>
+        </button>
+
+        <article className="overflow-hidden rounded-[40px] bg-white shadow-xl border border-slate-100">
+          {/* Foto Cover */}
+          {data.image_url && data.image_url !== "-" && (
+            <div className="h-[260px] md:h-[360px] overflow-hidden">
+              <img src={data.image_url} alt={data.title} className="h-full w-full object-cover" />
+            </div>
+          )}
+
+          <div className="p-8 md:p-10 space-y-6">
+            <div>
+              <span className="text-[10px] font-black uppercase tracking-widest text-indigo-600">{data.category}</span>
+              <h1 className="mt-2 text-3xl md:text-4xl font-bold text-slate-900 font-playfair leading-tight">
+                {data.title}
+              </h1>
+              <div className="mt-4 flex items-center gap-4 text-xs text-slate-400">
+                <span className="flex items-center gap-1">
+                  <FiCalendar />
+                  {new Date(data.published_at).toLocaleDateString("id-ID", { day: "numeric", month: "long", year: "numeric" })}
+                </span>
+                <span className="flex items-center gap-1">
+                  <FiClock /> {data.read_minutes} min read
+                </span>
+              </div>
+            </div>
+
+            <div
+              className="border-t border-slate-100 pt-6 text-slate-700 leading-relaxed space-y-4 [&_a]:text-indigo-600 [&_a]:underline [&_h2]:text-xl [&_h2]:font-bold [&_h3]:font-bold [&_ul]:list-disc [&_ul]:pl-6 [&_ol]:list-decimal [&_ol]:pl-6"
+              dangerouslySetInnerHTML={{ __html: data.content_html }}
+            />
+          </div>
+        </article>
+      </div>
+    </main>
+  );
+};
+
+export default NewsDetail;
@@ -0,0 +1,122 @@
+import React, { useState, FormEvent } from "react";
+import { useNavigate, useSearchParams } from "react-router-dom";
+
+// Halaman dari link email "lupa password": /reset-password?token=...
+const ResetPasswordPage: React.FC = () => {
+  const navigate = useNavigate();
+  const [searchParams] = useSearchParams();
+  const token = searchParams.get("token") ?? "";
+
+  const [password, setPassword] = useState("");
+  const [confirm, setConfirm] = useState("");
+  const [isLoading, setIsLoading] = useState(false);
+  const [error, setError] = useState("");
+  const [success, setSuccess] = useState("");
+
+  const API_BASE = import.meta.env.VITE_API_BASE_URL || "http://localhost:3000";
+
+  const handleSubmit = async (e: FormEvent) => {
+    e.preventDefault();
+    setError("");
+
+    if (!password) {
+      setError("Kata sandi baru tidak boleh kosong.");
+      return;
+    }
+    if (password !== confirm) {
+      setError("Konfirmasi kata sandi tidak sama.");
+      return;
+    }
+
+    try {
+      setIsLoading(true);
+      const response = await fetch(`${API_BASE}/reset_password`, {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify({ token, new_password: password }),
+      });
+      const data = await response.json().catch(() => null);
+
+      if (!response.ok) {
+        // 422: password ditolak aturan (token belum terpakai, boleh coba lagi). 401: link tidak valid / kedaluwarsa
+        const fieldMessage = data?.field_errors?.[0]?.message;
+        setError(fieldMessage || data?.message || "Link reset tidak valid atau sudah kedaluwarsa.");
+        return;
+      }
+
+      setSuccess(data?.message || "Password berhasil diganti, silakan login");
+    } catch (err) {
+      console.error("Reset password error:", err);
+      setError("Tidak bisa terhubung ke server. Coba lagi nanti.");
+    } finally {
+      setIsLoading(false);
+    }
+  };
+
+  return (
+    <div className="min-h-screen flex items-center justify-center bg-gradient-to-br from-white to-[#f5f4ff]">
+      <div className="w-[min(420px,92%)] bg-white shadow-[0_18px_45px_rgba(12,27,76,0.14)] rounded-3xl p-8 border border-[#E3E6F5]">
+        <div className="mb-6 text-center">
+          <p className="text-[11px] font-semibold tracking-[0.18em] text-[#5E6282] uppercase">Reset password</p>
+          <h1 className="mt-2 text-2xl font-extrabold text-[#181E4B]">Buat kata sandi baru</h1>
+        </div>
+
+        {!token ? (
+          <p className="text-center text-xs text-red-600">Link reset tidak lengkap. Buka lagi link dari email.</p>
+        ) : success ? (
+          <div className="space-y-4 text-center">
+            <p className="text-sm text-green-700">{success}</p>
+            <button
+              onClick={() => navigate("/login")}
+              className="w-full rounded-2xl py-2.5 text-sm font-semibold text-white bg-[#0f1f56] hover:opacity-95"
+            >
+              Ke halaman login
+            </button>
+          </div>
+        ) : (
+          <form onSubmit={handleSubmit} className="space-y-4">
+            {error && (
+              <div className="p-3 bg-red-50 border border-red-200 rounded-xl text-center">
+                <p className="text-xs text-red-600 font-medium">{error}</p>
+              </div>
+            )}
+
+            <div className="space-y-1">
+              <label className="block text-xs font-medium text-[#181E4B]">Kata sandi baru</label>
+              <input
+                type="password"
+                className="w-full rounded-2xl border border-[#E3E6F5] px-3.5 py-2.5 text-sm outline-none focus:border-[#0f1f56] focus:ring-1 focus:ring-[#0f1f56]/40"
+                placeholder="••••••••"
+                value={password}
+                onChange={(e) => { setPassword(e.target.value); setError(""); }}
+              />
+            </div>
+
+            <div className="space-y-1">
+              <label className="block text-xs font-medium text-[#181E4B]">Ulangi kata sandi baru</label>
+              <input
+                type="password"
+                className="w-full rounded-2xl border border-[#E3E6F5] px-3.5 py-2.5 text-sm outline-none focus:border-[#0f1f56] focus:ring-1 focus:ring-[#0f1f56]/40"
+                placeholder="••••••••"
+                value={confirm}
+                onChange={(e) => { setConfirm(e.target.value); setError(""); }}
+              />
+            </div>
+
+            <button
+              type="submit"
+              disabled={isLoading}
+              className={`w-full rounded-2xl py-2.5 text-sm font-semibold text-white transition ${
+                isLoading ? "bg-gray-400 cursor-not-allowed" : "bg-[#0f1f56] hover:opacity-95"
+              }`}
+            >
+              {isLoading ? "Memproses..." : "Simpan kata sandi"}
+            </button>
+          </form>
+        )}
+      </div>
+    </div>
+  );
+};
+
+export default ResetPasswordPage;
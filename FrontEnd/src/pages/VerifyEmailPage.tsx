@@ -0,0 +1,73 @@
+import React, { useEffect, useRef, useState } from "react";
+import { useNavigate, useSearchParams } from "react-router-dom";
+
+// Halaman dari link email verifikasi: /verify-email?token=...
+const VerifyEmailPage: React.FC = () => {
+  const navigate = useNavigate();
+  const [searchParams] = useSearchParams();
+  const token = searchParams.get("token") ?? "";
+
+  const [status, setStatus] = useState<"loading" | "success" | "error">("loading");
+  const [message, setMessage] = useState("");
+  // Token hanya bisa dipakai sekali, jangan kirim dua kali (StrictMode menjalankan effect dua kali di dev)
+  const sent = useRef(false);
+
+  const API_BASE = import.meta.env.VITE_API_BASE_URL || "http://localhost:3000";
+
+  useEffect(() => {
+    if (!token) {
+      setStatus("error");
+      setMessage("Link verifikasi tidak lengkap. Buka lagi link dari email.");
+      return;
+    }
+    if (sent.current) return;
+    sent.current = true;
+
+    const verify = async () => {
+      try {
+        const response = await fetch(`${API_BASE}/verify_email`, {
+          method: "POST",
+          headers: { "Content-Type": "application/json" },
+          body: JSON.stringify({ token }),
+        });
+        const data = await response.json().catch(() => null);
+        if (!response.ok) {
+          setStatus("error");
+          setMessage(data?.message || "Link verifikasi tidak valid atau sudah kedaluwarsa.");
+          return;
+        }
+        setStatus("success");
+        setMessage(data?.message || "Email berhasil diverifikasi");
+      } catch (err) {
+        console.error("Verify email error:", err);
+        setStatus("error");
+        setMessage("Tidak bisa terhubung ke server. Coba lagi nanti.");
+      }
+    };
+
+    verify();
+  }, [token, API_BASE]);
+
+  return (
+    <div className="min-h-screen flex items-center justify-center bg-gradient-to-br from-white to-[#f5f4ff]">
+      <div className="w-[min(420px,92%)] bg-white shadow-[0_18px_45px_rgba(12,27,76,0.14)] rounded-3xl p-8 border border-[#E3E6F5] text-center space-y-4">
+        <p className="text-[11px] font-semibold tracking-[0.18em] text-[#5E6282] uppercase">Verifikasi email</p>
+
+        {status === "loading" && <p className="text-sm text-[#5E6282] animate-pulse">Memverifikasi email...</p>}
+        {status === "success" && <p className="text-sm text-green-700">{message}</p>}
+        {status === "error" && <p className="text-sm text-red-600">{message}</p>}
+
+        {status !== "loading" && (
+          <button
+            onClick={() => navigate(status === "success" ? "/login" : "/")}
+            className="w-full rounded-2xl py-2.5 text-sm font-semibold text-white bg-[#0f1f56] hover:opacity-95"
+          >
+            {status === "success" ? "Ke halaman login" : "Kembali ke beranda"}
+          </button>
+        )}
+      </div>
+    </div>
+  );
+};
+
+export default VerifyEmailPage;
@@ -13,7 +13,7 @@ import {
 
 const WisataDetail: React.FC = () => {
   const navigate = useNavigate();
-  const { slug } = useParams<{ slug: string }>(); // slug berisi Unique ID (ALAM-10 atau EDU-10)
+  const { slug } = useParams<{ slug: string }>(); // "<PREFIX>-<slug>" (ALAM-curug-cipendok), link lama "<PREFIX>-<id>" (EDU-10) tetap jalan
   const [data, setData] = useState<any>(null);
   const [loading, setLoading] = useState(true);
   
@@ -26,25 +26,32 @@ const WisataDetail: React.FC = () => {
       try {
         setLoading(true);
         
-        // Memecah Unique ID (Prefix ID)
-        const parts = slug.split("-");
-        if (parts.length < 2) {
+        // Memecah "<PREFIX>-<slug atau id>", slug sendiri boleh mengandung "-"
+        const dash = slug.indexOf("-");
+        if (dash < 1) {
             setLoading(false);
             return;
         }
         
-        const prefix = parts[0]; // "ALAM" atau "EDU"
-        const id = parts[1];
+        const prefix = slug.slice(0, dash); // "ALAM" atau "EDU"
+        const key = slug.slice(dash + 1);
         
         // Menentukan endpoint berdasarkan prefix
         const endpoint = prefix === "EDU" ? "wisata_pendidikan" : "wisata_alam";
 
-        const response = await fetch(`${API_BASE}/${endpoint}`);
-        const list = await response.json();
-        
-        // Cari data yang ID-nya cocok
-        const found = list.find((item: any) => String(item.id) === String(id));
+        // Slug dulu (slug lama di-redirect server ke slug terbaru), angka yang bukan slug = id dari link lama
+        let response = await fetch(`${API_BASE}/${endpoint}/slug/${encodeURIComponent(key)}`);
+        if (!response.ok && /^\d+$/.test(key)) {
+          response = await fetch(`${API_BASE}/${endpoint}/${key}`);
+        }
+        if (!response.ok) {
+          setData(null);
+          return;
+        }
+        const found = await response.json();
         setData(found);
+        // Selalu tampilkan URL kanonik (sama dengan sitemap)
+        if (found.slug && found.slug !== key) navigate(`/wisata/${prefix}-${found.slug}`, { replace: true });
       } catch (e) {
         console.error("Error fetching detail:", e);
       } finally {
@@ -53,7 +60,7 @@ const WisataDetail: React.FC = () => {
     };
 
     fetchDetail();
-  }, [slug, API_BASE]);
+  }, [slug, API_BASE, navigate]);
 
   const handleOpenMaps = () => {
     if (data?.alamat) {
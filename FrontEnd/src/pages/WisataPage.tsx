@@ -8,6 +8,7 @@ type Facility = string;
 type WisataUI = {
   uniqueId: string;
   id: string;
+  detailPath: string; // /wisata/ALAM-<slug>, sama dengan URL di sitemap
   name: string;
   description: string;
   imageUrl: string;
@@ -86,6 +87,7 @@ const WisataPage: React.FC = () => {
             const mapToUI = (raw: any, prefix: string) => ({
                 uniqueId: `${prefix}-${raw.id}`,
                 id: String(raw.id),
+                detailPath: `/wisata/${prefix}-${raw.slug ?? raw.id}`,
                 name: raw.nama_tempat || raw.name,
                 description: raw.deskripsi && raw.deskripsi !== "-" ? raw.deskripsi : `Jelajahi keindahan destinasi ${raw.nama_tempat}`,
                 imageUrl: raw.link_foto,
@@ -197,7 +199,7 @@ const WisataPage: React.FC = () => {
           <div className="mt-8 md:mt-10">
             <div className="grid grid-cols-1 sm:grid-cols-2 lg:grid-cols-3 gap-6 md:gap-8">
               {filteredWisatas.slice(0, visibleLimit).map((w) => (
-                <Link key={w.uniqueId} to={`/wisata/${w.uniqueId}`} className="group h-full">
+                <Link key={w.uniqueId} to={w.detailPath} className="group h-full">
                   <article className="bg-white rounded-[24px] md:rounded-[32px] shadow-sm hover:shadow-xl hover:-translate-y-1 transition-all duration-300 overflow-hidden flex flex-col h-full border border-slate-100">
                     <div className="h-48 md:h-56 overflow-hidden relative">
                       <img 